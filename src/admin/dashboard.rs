@@ -0,0 +1,340 @@
+//! Built-in admin dashboard for sessions, auth failures and configuration.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{Html, IntoResponse, Redirect},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::audit::{AuditEvent, AuditEventKind, MemoryAuditSink};
+use crate::auth::TokenStore;
+use crate::bootstrap::LogLevelHandle;
+use crate::config::BaseConfig;
+use crate::transport::Sessions;
+use crate::TokenAuthLayer;
+
+/// The dashboard page, a single HTML file using the JSON routes below it.
+const INDEX_HTML: &str = include_str!("../../web/admin/index.html");
+
+/// Number of auth failures returned by `/admin/auth-failures`.
+const AUTH_FAILURES_LIMIT: usize = 50;
+
+/// Admin dashboard served under `/admin/`.
+///
+/// Shows the open SSE sessions with their principals and message counts,
+/// recent authentication failures, a configuration summary and the log
+/// level, and lets operators close sessions. The page is backed by JSON
+/// routes that can also be used directly:
+///
+/// - `/admin/sessions` and `/admin/notifications` - see
+///   [`sessions_router`](super::sessions_router)
+/// - `GET /admin/auth-failures` - the latest auth failures, newest first
+/// - `GET /admin/config` - `{"summary": "..."}`
+/// - `GET`/`PUT /admin/log-level` - see [`log_level_router`](super::log_level_router)
+///
+/// Dashboards created with [`new`](Self::new) are not protected by
+/// themselves, like the other admin routers; [`protected`](Self::protected)
+/// applies the auth layer to the dashboard's routes.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::{admin::Dashboard, audit::MemoryAuditSink, config::watch_config};
+///
+/// let audit = Arc::new(MemoryAuditSink::with_capacity(1000));
+/// let config = watch_config(Some("config.env".into()), Duration::from_secs(5))?;
+/// let dashboard = Dashboard::protected(server.sessions(), tokens, "admin")
+///     .audit(audit.clone())
+///     .config(config)
+///     .log_level(log_level_handle().unwrap())
+///     .router();
+/// let app = app.merge(dashboard);
+/// ```
+pub struct Dashboard {
+    sessions: Sessions,
+    audit: Option<Arc<MemoryAuditSink>>,
+    config: Option<watch::Receiver<Arc<BaseConfig>>>,
+    log_level: Option<LogLevelHandle>,
+    auth: Option<(TokenStore, String)>,
+}
+
+#[derive(Clone)]
+struct DashboardState {
+    audit: Option<Arc<MemoryAuditSink>>,
+    config: Option<watch::Receiver<Arc<BaseConfig>>>,
+}
+
+/// Body of `/admin/config` responses.
+#[derive(Serialize)]
+struct ConfigSummary {
+    summary: Option<String>,
+}
+
+impl Dashboard {
+    /// Create a dashboard for the sessions of an
+    /// [`AuthSseServer`](crate::transport::AuthSseServer).
+    pub fn new(sessions: Sessions) -> Self {
+        Self {
+            sessions,
+            audit: None,
+            config: None,
+            log_level: None,
+            auth: None,
+        }
+    }
+
+    /// Create a dashboard only served to principals of `tokens` with
+    /// `scope`.
+    ///
+    /// Rejected requests are recorded to the [`audit`](Self::audit) sink, so
+    /// they show up as auth failures.
+    pub fn protected(sessions: Sessions, tokens: TokenStore, scope: &str) -> Self {
+        Self {
+            auth: Some((tokens, scope.to_string())),
+            ..Self::new(sessions)
+        }
+    }
+
+    /// Show auth failures recorded in `sink`.
+    ///
+    /// Pass the same sink to
+    /// [`TokenAuthLayer::with_audit`](crate::TokenAuthLayer::with_audit).
+    pub fn audit(mut self, sink: Arc<MemoryAuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    /// Show the [`summary`](BaseConfig::summary) of the current config, e.g.
+    /// as published by [`watch_config`](crate::config::watch_config).
+    ///
+    /// For a config that never changes, pass the receiver of
+    /// `watch::channel(Arc::new(config))`.
+    pub fn config(mut self, config: watch::Receiver<Arc<BaseConfig>>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Show and change the log level through `handle`.
+    pub fn log_level(mut self, handle: LogLevelHandle) -> Self {
+        self.log_level = Some(handle);
+        self
+    }
+
+    /// Build the router serving the dashboard and its JSON routes.
+    pub fn router(self) -> Router {
+        let state = DashboardState {
+            audit: self.audit.clone(),
+            config: self.config,
+        };
+        let router = Router::new()
+            .route("/admin", get(|| async { Redirect::permanent("admin/") }))
+            .route("/admin/", get(index))
+            .route("/admin/auth-failures", get(auth_failures))
+            .route("/admin/config", get(config_summary))
+            .with_state(state)
+            .merge(super::sessions_router(self.sessions));
+        let router = match self.log_level {
+            Some(handle) => router.merge(super::log_level_router(handle)),
+            None => router,
+        };
+        let Some((tokens, scope)) = self.auth else {
+            return router;
+        };
+        let mut auth = TokenAuthLayer::with_store(tokens).require_scope("/admin", &scope);
+        if let Some(audit) = self.audit {
+            auth = auth.with_audit(audit);
+        }
+        router.layer(auth)
+    }
+}
+
+async fn index() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "no-cache")], Html(INDEX_HTML))
+}
+
+async fn auth_failures(State(state): State<DashboardState>) -> Json<Vec<AuditEvent>> {
+    let events = state.audit.map(|sink| sink.events()).unwrap_or_default();
+    Json(
+        events
+            .into_iter()
+            .rev()
+            .filter(|event| matches!(event.kind, AuditEventKind::AuthFailed { .. }))
+            .take(AUTH_FAILURES_LIMIT)
+            .collect(),
+    )
+}
+
+async fn config_summary(State(state): State<DashboardState>) -> Json<ConfigSummary> {
+    Json(ConfigSummary {
+        summary: state.config.map(|config| config.borrow().summary()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditSink, AuthFailure};
+    use crate::auth::{Principal, TokenEntry};
+    use crate::transport::AuthSseServer;
+    use crate::TokenAuthLayer;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::util::ServiceExt;
+
+    fn request(method: &str, uri: &str, token: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn json(response: axum::response::Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn app(server: &AuthSseServer, sse: Router, audit: Arc<MemoryAuditSink>) -> Router {
+        let dashboard = Dashboard::new(server.sessions())
+            .audit(audit.clone())
+            .router();
+        sse.merge(dashboard).layer(
+            TokenAuthLayer::new("admin".into())
+                .with_token(
+                    "client".into(),
+                    Principal::new("client").with_scopes(["read"]),
+                )
+                .require_scope("/admin", "admin")
+                .with_audit(audit),
+        )
+    }
+
+    #[tokio::test]
+    async fn serves_page_behind_admin_scope() {
+        let (server, sse) = AuthSseServer::new();
+        let app = app(&server, sse, Arc::new(MemoryAuditSink::new()));
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/admin/", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app
+            .oneshot(request("GET", "/admin/sessions", "client"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn lists_and_closes_sessions() {
+        let (server, sse) = AuthSseServer::new();
+        let app = app(&server, sse, Arc::new(MemoryAuditSink::new()));
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/sse", "client"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/admin/sessions", "admin"))
+            .await
+            .unwrap();
+        let sessions = json(response).await;
+        assert_eq!(sessions[0]["principal"], "client");
+        let id = sessions[0]["id"].as_str().unwrap();
+
+        let uri = format!("/admin/sessions/{id}");
+        let response = app
+            .clone()
+            .oneshot(request("DELETE", &uri, "admin"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = app.oneshot(request("DELETE", &uri, "admin")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        drop(response);
+        assert!(server.sessions().list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn protects_itself_and_shows_current_config() {
+        let (server, _sse) = AuthSseServer::new();
+        let tokens = TokenStore::new([
+            TokenEntry::new("admin", Principal::new("admin").with_scopes(["admin"])),
+            TokenEntry::new("client", Principal::new("client")),
+        ]);
+        let audit = Arc::new(MemoryAuditSink::new());
+        let mut config = BaseConfig::from_env();
+        config.port = 3001;
+        let (tx, rx) = watch::channel(Arc::new(config.clone()));
+        let app = Dashboard::protected(server.sessions(), tokens, "admin")
+            .audit(audit.clone())
+            .config(rx)
+            .router();
+
+        for (token, status) in [
+            ("client", StatusCode::FORBIDDEN),
+            ("wrong", StatusCode::UNAUTHORIZED),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(request("GET", "/admin/config", token))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{token}");
+        }
+        assert_eq!(audit.events().len(), 2);
+
+        config.port = 3002;
+        tx.send(Arc::new(config)).unwrap();
+        let response = app
+            .oneshot(request("GET", "/admin/config", "admin"))
+            .await
+            .unwrap();
+        let summary = json(response).await;
+        assert!(summary["summary"].as_str().unwrap().contains(":3002"));
+    }
+
+    #[tokio::test]
+    async fn shows_recent_auth_failures() {
+        let (server, sse) = AuthSseServer::new();
+        let audit = Arc::new(MemoryAuditSink::new());
+        audit.record(&AuditEvent::new(AuditEventKind::SessionOpened));
+        let app = app(&server, sse, audit);
+
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/sse", "wrong"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(request("GET", "/admin/auth-failures", "admin"))
+            .await
+            .unwrap();
+        let failures = json(response).await;
+        assert_eq!(failures.as_array().unwrap().len(), 1);
+        assert_eq!(
+            failures[0]["reason"],
+            serde_json::to_value(AuthFailure::InvalidToken).unwrap()
+        );
+        assert_eq!(failures[0]["path"], "/sse");
+    }
+}
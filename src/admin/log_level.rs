@@ -0,0 +1,111 @@
+//! Route for reading and changing the log filter at runtime.
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::bootstrap::{LogLevelError, LogLevelHandle};
+
+/// Body of requests to and responses from `/admin/log-level`.
+#[derive(Debug, Deserialize, Serialize)]
+struct LogLevel {
+    /// Filter directives in `EnvFilter` syntax, e.g. `my_server=debug,info`.
+    filter: String,
+}
+
+async fn get_log_level(State(handle): State<LogLevelHandle>) -> Json<LogLevel> {
+    Json(LogLevel {
+        filter: handle.current(),
+    })
+}
+
+async fn put_log_level(
+    State(handle): State<LogLevelHandle>,
+    Json(LogLevel { filter }): Json<LogLevel>,
+) -> Result<Json<LogLevel>, (StatusCode, String)> {
+    handle.set(&filter).map_err(|e| match e {
+        LogLevelError::InvalidFilter(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+        LogLevelError::Reload(_) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    Ok(Json(LogLevel {
+        filter: handle.current(),
+    }))
+}
+
+/// Router serving `GET` and `PUT /admin/log-level`.
+///
+/// `GET` returns the active filter as `{"filter": "..."}`; `PUT` with the same
+/// body replaces it and returns the new filter, or `400` if the directives
+/// cannot be parsed.
+pub fn log_level_router(handle: LogLevelHandle) -> Router {
+    Router::new()
+        .route("/admin/log-level", get(get_log_level).put(put_log_level))
+        .with_state(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenAuthLayer;
+    use axum::{body::Body, http::Request};
+    use tower::util::ServiceExt;
+    use tracing_subscriber::{prelude::*, EnvFilter};
+
+    fn put(filter: &str, token: &str) -> Request<Body> {
+        Request::builder()
+            .method("PUT")
+            .uri("/admin/log-level")
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(format!(r#"{{"filter":"{filter}"}}"#)))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn changes_filter_at_runtime() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let app = log_level_router(handle.clone()).layer(TokenAuthLayer::new("admin".into()));
+
+        let response = app
+            .clone()
+            .oneshot(put("my_server=debug,info", "admin"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(handle.current(), "my_server=debug,info");
+
+        let request = Request::builder()
+            .uri("/admin/log-level")
+            .header("Authorization", "Bearer admin")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], br#"{"filter":"my_server=debug,info"}"#);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_filter() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let app = log_level_router(handle.clone());
+
+        let response = app.oneshot(put("my_server=loud", "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(handle.current(), "info");
+    }
+
+    #[tokio::test]
+    async fn requires_authentication() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let app = log_level_router(handle.clone()).layer(TokenAuthLayer::new("admin".into()));
+
+        let response = app.oneshot(put("trace", "wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(handle.current(), "info");
+    }
+}
@@ -0,0 +1,27 @@
+//! Admin HTTP routes for operating a running server.
+//!
+//! The routers in this module are not protected by themselves. Merge them
+//! behind a [`TokenAuthLayer`](crate::TokenAuthLayer) so only operators can
+//! reach them.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use mcp_core::{admin, bootstrap::log_level_handle, TokenAuthLayer};
+//!
+//! let admin = admin::log_level_router(log_level_handle().unwrap())
+//!     .layer(TokenAuthLayer::new(admin_token));
+//! let app = app.merge(admin);
+//! ```
+
+#[cfg(feature = "dashboard")]
+mod dashboard;
+mod log_level;
+#[cfg(feature = "transport")]
+mod sessions;
+
+#[cfg(feature = "dashboard")]
+pub use dashboard::Dashboard;
+pub use log_level::log_level_router;
+#[cfg(feature = "transport")]
+pub use sessions::{sessions_router, sessions_router_with, DEFAULT_BROADCAST_METHODS};
@@ -0,0 +1,293 @@
+//! Routes for listing, closing and notifying SSE sessions.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use rmcp::model::ServerNotification;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Principal;
+use crate::transport::{notification_method, SessionInfo, Sessions};
+
+/// Notification methods [`sessions_router`] lets operators broadcast.
+pub const DEFAULT_BROADCAST_METHODS: &[&str] = &[
+    "notifications/message",
+    "notifications/resources/list_changed",
+    "notifications/tools/list_changed",
+    "notifications/prompts/list_changed",
+];
+
+#[derive(Clone)]
+struct SessionsState {
+    sessions: Sessions,
+    broadcast_methods: Arc<[String]>,
+}
+
+/// Body of `POST /admin/notifications` requests.
+#[derive(Deserialize)]
+struct Broadcast {
+    /// Sessions to notify; all sessions if absent.
+    #[serde(default)]
+    sessions: Option<Vec<String>>,
+    /// The notification's `method` and `params`.
+    #[serde(flatten)]
+    notification: ServerNotification,
+}
+
+/// Body of `POST /admin/notifications` responses.
+#[derive(Serialize)]
+struct Broadcasted {
+    /// Number of sessions the notification was queued for.
+    sent: usize,
+}
+
+async fn list_sessions(State(state): State<SessionsState>) -> Json<Vec<SessionInfo>> {
+    Json(state.sessions.list().await)
+}
+
+async fn get_session(
+    State(state): State<SessionsState>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionInfo>, StatusCode> {
+    state
+        .sessions
+        .get(&id)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn close_session(
+    State(state): State<SessionsState>,
+    operator: Option<Extension<Principal>>,
+    Path(id): Path<String>,
+) -> StatusCode {
+    let operator = operator.as_ref().map(|Extension(principal)| principal);
+    if state.sessions.close(&id, operator).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+async fn broadcast(
+    State(state): State<SessionsState>,
+    operator: Option<Extension<Principal>>,
+    Json(Broadcast {
+        sessions: ids,
+        notification,
+    }): Json<Broadcast>,
+) -> Result<Json<Broadcasted>, StatusCode> {
+    let method = notification_method(&notification);
+    if !state
+        .broadcast_methods
+        .iter()
+        .any(|allowed| allowed == method)
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let operator = operator.as_ref().map(|Extension(principal)| principal);
+    let sent = state
+        .sessions
+        .notify(notification, ids.as_deref(), operator)
+        .await;
+    Ok(Json(Broadcasted { sent }))
+}
+
+/// Router controlling the sessions of an
+/// [`AuthSseServer`](crate::transport::AuthSseServer).
+///
+/// - `GET /admin/sessions` lists the open sessions, oldest first.
+/// - `GET /admin/sessions/{id}` returns the principal, address and message
+///   counts of one session, or `404`.
+/// - `DELETE /admin/sessions/{id}` closes a session, ending its SSE stream
+///   and [`SseTransport`](crate::transport::SseTransport), and returns `204`,
+///   or `404` if it is not open.
+/// - `POST /admin/notifications` sends an MCP server notification, given as
+///   `{"method": "...", "params": {...}}`, to every session or to those
+///   listed in an optional `"sessions"` array, and returns
+///   `{"sent": <count>}`. Methods outside [`DEFAULT_BROADCAST_METHODS`] are
+///   rejected with `422`.
+///
+/// Closes and notifications are audited with the principal of the operator,
+/// if the auth layer authenticated one.
+pub fn sessions_router(sessions: Sessions) -> Router {
+    sessions_router_with(sessions, DEFAULT_BROADCAST_METHODS.iter().copied())
+}
+
+/// Like [`sessions_router`], but only lets operators broadcast the
+/// notification `methods`.
+pub fn sessions_router_with<I, T>(sessions: Sessions, methods: I) -> Router
+where
+    I: IntoIterator<Item = T>,
+    T: Into<String>,
+{
+    let state = SessionsState {
+        sessions,
+        broadcast_methods: methods.into_iter().map(Into::into).collect(),
+    };
+    Router::new()
+        .route("/admin/sessions", get(list_sessions))
+        .route(
+            "/admin/sessions/:id",
+            get(get_session).delete(close_session),
+        )
+        .route("/admin/notifications", post(broadcast))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Principal;
+    use crate::transport::AuthSseServer;
+    use crate::TokenAuthLayer;
+    use axum::{body::Body, http::Request};
+    use futures::StreamExt;
+    use tower::util::ServiceExt;
+
+    fn request(method: &str, uri: &str, token: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn json(response: axum::response::Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn controls_sessions() {
+        let (server, sse) = AuthSseServer::new();
+        let app = sse.merge(sessions_router(server.sessions())).layer(
+            TokenAuthLayer::new("admin".into())
+                .with_token(
+                    "client".into(),
+                    Principal::new("client").with_scopes(["read"]),
+                )
+                .require_scope("/admin", "admin"),
+        );
+        let call = |method: &str, uri: &str, token: &str, body: &str| {
+            app.clone().oneshot(request(method, uri, token, body))
+        };
+
+        let response = call("GET", "/sse", "client", "").await.unwrap();
+        let mut events = response.into_body().into_data_stream();
+        events.next().await.unwrap().unwrap();
+
+        let response = call("GET", "/admin/sessions", "client", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let sessions = json(call("GET", "/admin/sessions", "admin", "").await.unwrap()).await;
+        let id = sessions[0]["id"].as_str().unwrap().to_string();
+        let uri = format!("/admin/sessions/{id}");
+        let session = json(call("GET", &uri, "admin", "").await.unwrap()).await;
+        assert_eq!(session["principal"], "client");
+        assert_eq!(session["messages_sent"], 0);
+
+        let notification = format!(
+            r#"{{"method":"notifications/message","sessions":["{id}","gone"],
+                "params":{{"level":"warning","data":"restarting soon"}}}}"#
+        );
+        let response = call("POST", "/admin/notifications", "admin", &notification)
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["sent"], 1);
+        let chunk = events.next().await.unwrap().unwrap();
+        assert!(std::str::from_utf8(&chunk)
+            .unwrap()
+            .contains("restarting soon"));
+
+        for method in ["notifications/unknown", "notifications/cancelled"] {
+            let body = format!(r#"{{"method":"{method}","params":{{"requestId":1}}}}"#);
+            let response = call("POST", "/admin/notifications", "admin", &body)
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "{method}"
+            );
+        }
+
+        let response = call("DELETE", &uri, "admin", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(events.next().await.is_none());
+        let response = call("GET", &uri, "admin", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn audits_operator_actions() {
+        use crate::audit::{AuditEventKind, MemoryAuditSink};
+        use crate::transport::SseServerConfig;
+
+        let audit = Arc::new(MemoryAuditSink::new());
+        let (server, sse) = AuthSseServer::with_config(SseServerConfig {
+            audit: Some(audit.clone()),
+            ..Default::default()
+        });
+        let app = sse.merge(sessions_router(server.sessions())).layer(
+            TokenAuthLayer::new("client".into())
+                .with_token("admin".into(), Principal::new("ops").with_scopes(["admin"])),
+        );
+        let response = app
+            .clone()
+            .oneshot(request("GET", "/sse", "client", ""))
+            .await
+            .unwrap();
+        let mut events = response.into_body().into_data_stream();
+        events.next().await.unwrap().unwrap();
+        let id = server.sessions().list().await[0].id.clone();
+
+        let notification = r#"{"method":"notifications/tools/list_changed"}"#;
+        let response = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/admin/notifications",
+                "admin",
+                notification,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let uri = format!("/admin/sessions/{id}");
+        let response = app
+            .oneshot(request("DELETE", &uri, "admin", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let recorded = audit.events();
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(
+            recorded[1].kind,
+            AuditEventKind::NotificationSent {
+                method: "notifications/tools/list_changed".to_string(),
+                sessions: 1,
+            }
+        );
+        assert_eq!(recorded[1].principal.as_deref(), Some("ops"));
+        assert_eq!(
+            recorded[2].kind,
+            AuditEventKind::SessionClosedByOperator {
+                operator: Some("ops".to_string()),
+            }
+        );
+        assert_eq!(recorded[2].principal.as_deref(), Some("default"));
+        assert_eq!(recorded[2].session_id.as_deref(), Some(id.as_str()));
+    }
+}
@@ -0,0 +1,216 @@
+//! Audit event types.
+
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::timestamp::format_rfc3339;
+
+/// Placeholder written instead of redacted values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Object keys whose values are treated as secrets (matched case-insensitively
+/// as substrings).
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "credential",
+    "private_key",
+];
+
+/// Why an authentication attempt was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthFailure {
+    /// No `Authorization` header was sent.
+    MissingCredentials,
+    /// The header used an unsupported scheme or could not be decoded.
+    MalformedCredentials,
+    /// The presented token is not valid.
+    InvalidToken,
+    /// The token is valid but lacks the scope the route requires.
+    MissingScope,
+}
+
+/// What happened.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// An HTTP request was rejected by the auth layer.
+    AuthFailed {
+        /// Why the credentials were rejected.
+        reason: AuthFailure,
+        /// Request path.
+        path: String,
+    },
+    /// A client opened an SSE session.
+    SessionOpened,
+    /// An SSE session was closed.
+    SessionClosed,
+    /// An operator closed an SSE session through the admin routes.
+    SessionClosedByOperator {
+        /// Name of the operator's principal, if known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        operator: Option<String>,
+    },
+    /// An operator sent a notification to SSE sessions through the admin
+    /// routes.
+    NotificationSent {
+        /// Notification method.
+        method: String,
+        /// Number of sessions the notification was queued for.
+        sessions: usize,
+    },
+    /// A client called a tool.
+    ToolCalled {
+        /// Tool name.
+        tool: String,
+        /// Call arguments with secret values redacted.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        arguments: Option<Value>,
+    },
+}
+
+/// A single audit record.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AuditEvent {
+    /// RFC 3339 timestamp (UTC).
+    pub timestamp: String,
+    /// What happened.
+    #[serde(flatten)]
+    pub kind: AuditEventKind,
+    /// Name of the authenticated principal, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// Client address, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<IpAddr>,
+    /// MCP session the event belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+impl AuditEvent {
+    /// Create an event of the given kind timestamped now.
+    pub fn new(kind: AuditEventKind) -> Self {
+        Self {
+            timestamp: format_rfc3339(SystemTime::now()),
+            kind,
+            principal: None,
+            remote_addr: None,
+            session_id: None,
+        }
+    }
+
+    /// Set the principal.
+    pub fn principal(mut self, principal: impl Into<String>) -> Self {
+        self.principal = Some(principal.into());
+        self
+    }
+
+    /// Set the client address.
+    pub fn remote_addr(mut self, addr: Option<IpAddr>) -> Self {
+        self.remote_addr = addr;
+        self
+    }
+
+    /// Set the session id.
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// Replace the values of secret-looking object keys with `"[REDACTED]"`.
+///
+/// Keys are matched case-insensitively against a list of common names such as
+/// `password`, `token`, `secret` and `api_key`; nested objects and arrays are
+/// redacted recursively.
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_key(&key) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact(value)
+                    };
+                    (key, value)
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_KEYS.iter().any(|secret| key.contains(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_nested_secrets() {
+        let redacted = redact(json!({
+            "query": "rust",
+            "apiKey": "abc",
+            "auth": {"Password": "hunter2", "user": "bob"},
+            "headers": [{"X-Auth-Token": "t"}],
+        }));
+        assert_eq!(
+            redacted,
+            json!({
+                "query": "rust",
+                "apiKey": "[REDACTED]",
+                "auth": {"Password": "[REDACTED]", "user": "bob"},
+                "headers": [{"X-Auth-Token": "[REDACTED]"}],
+            })
+        );
+    }
+
+    #[test]
+    fn serializes_flat_record() {
+        let event = AuditEvent::new(AuditEventKind::ToolCalled {
+            tool: "search".to_string(),
+            arguments: Some(redact(json!({"token": "t"}))),
+        })
+        .principal("default")
+        .remote_addr(Some("127.0.0.1".parse().unwrap()))
+        .session_id("abc");
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["event"], "tool_called");
+        assert_eq!(value["tool"], "search");
+        assert_eq!(value["arguments"]["token"], "[REDACTED]");
+        assert_eq!(value["principal"], "default");
+        assert_eq!(value["remote_addr"], "127.0.0.1");
+        assert_eq!(value["session_id"], "abc");
+    }
+
+    #[test]
+    fn omits_unknown_fields() {
+        let event = AuditEvent::new(AuditEventKind::AuthFailed {
+            reason: AuthFailure::InvalidToken,
+            path: "/sse".to_string(),
+        });
+        let value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(value["event"], "auth_failed");
+        assert_eq!(value["reason"], "invalid_token");
+        assert!(value.get("principal").is_none());
+        assert!(value.get("session_id").is_none());
+    }
+}
@@ -0,0 +1,326 @@
+//! Append-only JSON-lines audit file with optional hash chaining.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{AuditEvent, AuditSink};
+
+/// `prev_hash` of the first record in a chain.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+struct Writer {
+    file: File,
+    last_hash: Option<String>,
+}
+
+/// [`AuditSink`] appending one JSON object per line to a file.
+///
+/// With hash chaining enabled, every record carries the `prev_hash` of the
+/// record before it and its own `hash`, the hex SHA-256 of the previous hash
+/// followed by the record serialized without `hash`. Editing, inserting or
+/// deleting a record breaks the chain, which [`verify_chain`] detects.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::{path::Path, sync::Arc};
+/// use mcp_core::audit::JsonLinesAuditSink;
+///
+/// let sink = JsonLinesAuditSink::in_data_path(Path::new("./data"))
+///     .unwrap()
+///     .with_hash_chain()
+///     .unwrap();
+/// let sink = Arc::new(sink);
+/// ```
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+    writer: Mutex<Writer>,
+}
+
+impl JsonLinesAuditSink {
+    /// Open (or create) the audit file at `path` for appending.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            writer: Mutex::new(Writer {
+                file,
+                last_hash: None,
+            }),
+        })
+    }
+
+    /// Open `<data_path>/audit/audit.jsonl`.
+    pub fn in_data_path(data_path: &Path) -> io::Result<Self> {
+        Self::open(data_path.join("audit").join("audit.jsonl"))
+    }
+
+    /// Enable hash chaining, continuing the chain of an existing file.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the file already holds
+    /// records that [`verify_chain`] rejects, such as ones written without
+    /// chaining or a final line torn by a crash or a full disk, since
+    /// appending to it would not yield a verifiable log. Move such a file
+    /// aside, or truncate it to its last complete line, to continue.
+    pub fn with_hash_chain(self) -> io::Result<Self> {
+        let last_hash = match chain_tail(&self.path) {
+            Ok((_, last_hash)) => last_hash,
+            Err(AuditChainError::Io(e)) => return Err(e),
+            Err(e @ AuditChainError::Broken { .. }) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {e}", self.path.display()),
+                ))
+            }
+        };
+        self.writer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last_hash = Some(last_hash);
+        Ok(self)
+    }
+
+    fn append(&self, event: &AuditEvent) -> io::Result<()> {
+        let mut record = serde_json::to_value(event)?;
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut hash = None;
+        if let (Some(prev_hash), Value::Object(map)) = (&writer.last_hash, &mut record) {
+            map.insert("prev_hash".to_string(), Value::String(prev_hash.clone()));
+            let record_hash = chain_hash(prev_hash, &record)?;
+            if let Value::Object(map) = &mut record {
+                map.insert("hash".to_string(), Value::String(record_hash.clone()));
+            }
+            hash = Some(record_hash);
+        }
+
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        writer.file.write_all(&line)?;
+        writer.file.flush()?;
+        // Only chain onto records that reached the file.
+        if hash.is_some() {
+            writer.last_hash = hash;
+        }
+        Ok(())
+    }
+}
+
+impl AuditSink for JsonLinesAuditSink {
+    fn record(&self, event: &AuditEvent) {
+        if let Err(e) = self.append(event) {
+            tracing::error!(path = %self.path.display(), error = %e, "failed to write audit event");
+        }
+    }
+}
+
+/// Hash of a record (serialized without `hash`) chained onto `prev_hash`.
+fn chain_hash(prev_hash: &str, record: &Value) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(serde_json::to_vec(record)?);
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+/// Error returned by [`verify_chain`].
+#[derive(Debug)]
+pub enum AuditChainError {
+    /// The file could not be read or a line is not valid JSON.
+    Io(io::Error),
+    /// The record on this (1-based) line does not continue the chain.
+    Broken {
+        /// Line number of the first record that fails verification.
+        line: usize,
+    },
+}
+
+impl fmt::Display for AuditChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read audit log: {e}"),
+            Self::Broken { line } => write!(f, "audit hash chain broken at line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for AuditChainError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Broken { .. } => None,
+        }
+    }
+}
+
+/// Verify the hash chain of an audit file written with
+/// [`JsonLinesAuditSink::with_hash_chain`].
+///
+/// Returns the number of records on success.
+pub fn verify_chain(path: &Path) -> Result<usize, AuditChainError> {
+    chain_tail(path).map(|(count, _)| count)
+}
+
+/// Verify the chain of `path`, returning the number of records and the hash
+/// to continue from.
+fn chain_tail(path: &Path) -> Result<(usize, String), AuditChainError> {
+    let file = File::open(path).map_err(AuditChainError::Io)?;
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut count = 0;
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(AuditChainError::Io)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = AuditChainError::Broken { line: index + 1 };
+        let mut record: Value =
+            serde_json::from_str(&line).map_err(|e| AuditChainError::Io(e.into()))?;
+        let Some(map) = record.as_object_mut() else {
+            return Err(broken);
+        };
+        let Some(Value::String(hash)) = map.remove("hash") else {
+            return Err(broken);
+        };
+        if map.get("prev_hash").and_then(Value::as_str) != Some(prev_hash.as_str()) {
+            return Err(broken);
+        }
+        if chain_hash(&prev_hash, &record).map_err(AuditChainError::Io)? != hash {
+            return Err(broken);
+        }
+        prev_hash = hash;
+        count += 1;
+    }
+
+    Ok((count, prev_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditEventKind, AuthFailure};
+
+    fn failure() -> AuditEvent {
+        AuditEvent::new(AuditEventKind::AuthFailed {
+            reason: AuthFailure::InvalidToken,
+            path: "/sse".to_string(),
+        })
+    }
+
+    fn read_lines(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writes_one_record_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = JsonLinesAuditSink::in_data_path(dir.path()).unwrap();
+        sink.record(&failure());
+        sink.record(&AuditEvent::new(AuditEventKind::SessionOpened).session_id("abc"));
+
+        let lines = read_lines(&dir.path().join("audit/audit.jsonl"));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "auth_failed");
+        assert_eq!(lines[1]["session_id"], "abc");
+        assert!(lines[0].get("hash").is_none());
+    }
+
+    #[test]
+    fn chain_verifies_and_continues_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let sink = JsonLinesAuditSink::open(&path)
+            .unwrap()
+            .with_hash_chain()
+            .unwrap();
+        sink.record(&failure());
+        sink.record(&failure());
+        drop(sink);
+
+        let sink = JsonLinesAuditSink::open(&path)
+            .unwrap()
+            .with_hash_chain()
+            .unwrap();
+        sink.record(&failure());
+
+        assert_eq!(verify_chain(&path).unwrap(), 3);
+    }
+
+    #[test]
+    fn refuses_to_chain_onto_unchained_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        JsonLinesAuditSink::open(&path).unwrap().record(&failure());
+
+        let err = JsonLinesAuditSink::open(&path)
+            .unwrap()
+            .with_hash_chain()
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(read_lines(&path).len(), 1);
+    }
+
+    #[test]
+    fn keeps_chain_when_a_write_fails() {
+        let file = OpenOptions::new().write(true).open("/dev/full").unwrap();
+        let sink = JsonLinesAuditSink {
+            path: PathBuf::from("/dev/full"),
+            writer: Mutex::new(Writer {
+                file,
+                last_hash: Some(GENESIS_HASH.to_string()),
+            }),
+        };
+
+        assert!(sink.append(&failure()).is_err());
+        let writer = sink.writer.lock().unwrap();
+        assert_eq!(writer.last_hash.as_deref(), Some(GENESIS_HASH));
+    }
+
+    #[test]
+    fn detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let sink = JsonLinesAuditSink::open(&path)
+            .unwrap()
+            .with_hash_chain()
+            .unwrap();
+        for _ in 0..3 {
+            sink.record(&failure());
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let tampered = contents.replacen("/sse", "/message", 1);
+        fs::write(&path, tampered).unwrap();
+        assert!(matches!(
+            verify_chain(&path),
+            Err(AuditChainError::Broken { line: 1 })
+        ));
+
+        let mut lines: Vec<&str> = contents.lines().collect();
+        lines.remove(1);
+        fs::write(&path, lines.join("\n")).unwrap();
+        assert!(matches!(
+            verify_chain(&path),
+            Err(AuditChainError::Broken { line: 2 })
+        ));
+    }
+}
@@ -0,0 +1,70 @@
+//! In-memory audit sink.
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use super::{AuditEvent, AuditSink};
+
+/// [`AuditSink`] keeping events in memory, e.g. for tests or for showing
+/// recent events in an admin UI.
+#[derive(Default)]
+pub struct MemoryAuditSink {
+    events: Mutex<VecDeque<AuditEvent>>,
+    capacity: Option<usize>,
+}
+
+impl MemoryAuditSink {
+    /// Create an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty sink keeping only the latest `capacity` events.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity: Some(capacity),
+        }
+    }
+
+    /// All events recorded so far, oldest first.
+    pub fn events(&self) -> Vec<AuditEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record(&self, event: &AuditEvent) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        if self.capacity == Some(events.len()) {
+            events.pop_front();
+        }
+        if self.capacity != Some(0) {
+            events.push_back(event.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditEventKind;
+
+    #[test]
+    fn keeps_latest_events() {
+        let sink = MemoryAuditSink::with_capacity(2);
+        for session in ["a", "b", "c"] {
+            sink.record(&AuditEvent::new(AuditEventKind::SessionOpened).session_id(session));
+        }
+        let sessions: Vec<_> = sink
+            .events()
+            .into_iter()
+            .filter_map(|event| event.session_id)
+            .collect();
+        assert_eq!(sessions, ["b", "c"]);
+    }
+}
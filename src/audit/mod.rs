@@ -0,0 +1,26 @@
+//! Audit log of authentication and session events.
+//!
+//! [`AuditSink`]s receive an [`AuditEvent`] whenever an authentication attempt
+//! fails, an SSE session is opened or closed, or a tool is called. Feed one to
+//! [`TokenAuthLayer::with_audit`](crate::TokenAuthLayer::with_audit) and
+//! [`SseServerConfig::audit`](crate::transport::SseServerConfig::audit).
+//!
+//! Events never contain tokens or passwords; tool arguments are passed
+//! through [`redact`] before they are recorded.
+
+mod event;
+mod jsonl;
+mod memory;
+
+pub use event::{redact, AuditEvent, AuditEventKind, AuthFailure};
+pub use jsonl::{verify_chain, AuditChainError, JsonLinesAuditSink};
+pub use memory::MemoryAuditSink;
+
+/// Destination for audit events.
+///
+/// Implementations must not block for long; they are called inline from
+/// request handlers.
+pub trait AuditSink: Send + Sync + 'static {
+    /// Record a single event.
+    fn record(&self, event: &AuditEvent);
+}
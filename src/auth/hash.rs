@@ -0,0 +1,224 @@
+//! Hashed token verification.
+//!
+//! Token files can list a hash instead of the plaintext token:
+//!
+//! - `sha256$<salt>$<digest>`: hex SHA-256 of pepper, salt and token
+//! - `$argon2id$...`: an Argon2 PHC string, keyed with the pepper as secret
+//!   (requires the `argon2` feature)
+//!
+//! Plaintext tokens are still accepted. All comparisons are constant-time.
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+const SHA256_PREFIX: &str = "sha256$";
+const SALT_LEN: usize = 16;
+
+/// How a token is stored.
+#[derive(Clone, PartialEq)]
+pub(crate) enum TokenSecret {
+    /// Plaintext, compared by digest so the length does not leak.
+    Plain([u8; 32]),
+    /// Salted and peppered SHA-256.
+    Sha256 { salt: Vec<u8>, digest: [u8; 32] },
+    /// Argon2 PHC string.
+    #[cfg(feature = "argon2")]
+    Argon2(std::sync::Arc<str>),
+}
+
+impl TokenSecret {
+    pub(crate) fn plain(token: &str) -> Self {
+        Self::Plain(Sha256::digest(token.as_bytes()).into())
+    }
+
+    /// Parse a token file entry: a hash in one of the supported formats, or a
+    /// plaintext token.
+    pub(crate) fn parse(value: &str) -> Result<Self, TokenHashError> {
+        if let Some(rest) = value.strip_prefix(SHA256_PREFIX) {
+            let (salt, digest) = rest.split_once('$').ok_or(TokenHashError::Malformed)?;
+            let salt = decode_hex(salt).ok_or(TokenHashError::Malformed)?;
+            let digest = decode_hex(digest)
+                .and_then(|digest| digest.try_into().ok())
+                .ok_or(TokenHashError::Malformed)?;
+            return Ok(Self::Sha256 { salt, digest });
+        }
+        if value.starts_with("$argon2") {
+            #[cfg(feature = "argon2")]
+            {
+                argon2::PasswordHash::new(value).map_err(|_| TokenHashError::Malformed)?;
+                return Ok(Self::Argon2(value.into()));
+            }
+            #[cfg(not(feature = "argon2"))]
+            return Err(TokenHashError::Unsupported("argon2"));
+        }
+        Ok(Self::plain(value))
+    }
+
+    /// Whether `token` matches, in time independent of where they differ.
+    pub(crate) fn verify(&self, token: &str, pepper: &[u8]) -> bool {
+        match self {
+            Self::Plain(digest) => {
+                let candidate: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+                candidate.ct_eq(digest).into()
+            }
+            Self::Sha256 { salt, digest } => sha256(token, salt, pepper).ct_eq(digest).into(),
+            #[cfg(feature = "argon2")]
+            Self::Argon2(hash) => {
+                use argon2::PasswordVerifier;
+
+                let (Ok(hash), Ok(argon2)) = (argon2::PasswordHash::new(hash), argon2_with(pepper))
+                else {
+                    return false;
+                };
+                // The verifier compares the output in constant time.
+                argon2.verify_password(token.as_bytes(), &hash).is_ok()
+            }
+        }
+    }
+}
+
+fn sha256(token: &str, salt: &[u8], pepper: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(pepper);
+    hasher.update(salt);
+    hasher.update(token.as_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(feature = "argon2")]
+fn argon2_with(pepper: &[u8]) -> Result<argon2::Argon2<'_>, argon2::Error> {
+    if pepper.is_empty() {
+        return Ok(argon2::Argon2::default());
+    }
+    argon2::Argon2::new_with_secret(
+        pepper,
+        argon2::Algorithm::default(),
+        argon2::Version::default(),
+        argon2::Params::default(),
+    )
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn random_salt() -> [u8; SALT_LEN] {
+    rand::random()
+}
+
+/// Hash `token` as `sha256$<salt>$<digest>` for a token file.
+///
+/// Pass the same `pepper` to [`TokenStore::with_pepper`](super::TokenStore::with_pepper);
+/// it is never written to the file. Use a long random token: SHA-256 is fast,
+/// so the hash only protects tokens that cannot be guessed.
+pub fn hash_token(token: &str, pepper: &str) -> String {
+    let salt = random_salt();
+    let digest = sha256(token, &salt, pepper.as_bytes());
+    format!(
+        "{SHA256_PREFIX}{}${}",
+        encode_hex(&salt),
+        encode_hex(&digest)
+    )
+}
+
+/// Hash `token` as an Argon2id PHC string for a token file.
+///
+/// Argon2 is deliberately slow and runs on every authenticated request, so
+/// prefer [`hash_token`] for tokens used at a high request rate.
+#[cfg(feature = "argon2")]
+pub fn hash_token_argon2(token: &str, pepper: &str) -> Result<String, TokenHashError> {
+    use argon2::password_hash::{PasswordHasher, SaltString};
+
+    let salt = SaltString::encode_b64(&random_salt()).map_err(|_| TokenHashError::Malformed)?;
+    let argon2 = argon2_with(pepper.as_bytes()).map_err(|_| TokenHashError::Malformed)?;
+    argon2
+        .hash_password(token.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| TokenHashError::Malformed)
+}
+
+/// Error returned for token hashes that cannot be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenHashError {
+    /// The value looks like a hash but cannot be parsed.
+    Malformed,
+    /// The hash algorithm needs a disabled crate feature.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for TokenHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "malformed token hash"),
+            Self::Unsupported(feature) => {
+                write!(f, "token hash requires the `{feature}` feature")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenHashError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_sha256_hashes_with_pepper() {
+        let hash = hash_token("s3cret", "pepper");
+        assert!(hash.starts_with("sha256$"));
+        let secret = TokenSecret::parse(&hash).unwrap();
+        assert!(secret.verify("s3cret", b"pepper"));
+        assert!(!secret.verify("s3cret", b"other"));
+        assert!(!secret.verify("s3cre", b"pepper"));
+        assert_ne!(hash, hash_token("s3cret", "pepper"));
+    }
+
+    #[test]
+    fn verifies_plaintext_tokens() {
+        let secret = TokenSecret::parse("s3cret").unwrap();
+        assert!(secret.verify("s3cret", b""));
+        assert!(!secret.verify("s3cret2", b""));
+    }
+
+    #[test]
+    fn rejects_malformed_hashes() {
+        for value in ["sha256$zz$00", "sha256$00", "sha256$00$0011"] {
+            assert!(
+                matches!(TokenSecret::parse(value), Err(TokenHashError::Malformed)),
+                "{value}"
+            );
+        }
+    }
+
+    #[cfg(feature = "argon2")]
+    #[test]
+    fn verifies_argon2_hashes() {
+        let hash = hash_token_argon2("s3cret", "pepper").unwrap();
+        let secret = TokenSecret::parse(&hash).unwrap();
+        assert!(secret.verify("s3cret", b"pepper"));
+        assert!(!secret.verify("s3cret", b""));
+        assert!(!secret.verify("wrong", b"pepper"));
+    }
+
+    #[cfg(not(feature = "argon2"))]
+    #[test]
+    fn argon2_needs_feature() {
+        assert_eq!(
+            TokenSecret::parse("$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA").err(),
+            Some(TokenHashError::Unsupported("argon2"))
+        );
+    }
+}
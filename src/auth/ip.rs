@@ -0,0 +1,535 @@
+//! CIDR-based client access control with trusted proxy support.
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, HeaderMap, Request, StatusCode},
+    response::Response,
+};
+use std::{
+    fmt,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8` or `::1/128`.
+///
+/// A bare address parses as a single-host network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    /// Create a network, masking host bits of `addr`.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, IpNetParseError> {
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max {
+            return Err(IpNetParseError::InvalidPrefix);
+        }
+        let addr = match addr {
+            IpAddr::V4(v4) => IpAddr::V4((u32::from(v4) & mask32(prefix_len)).into()),
+            IpAddr::V6(v6) => IpAddr::V6((u128::from(v6) & mask128(prefix_len)).into()),
+        };
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// IPv4 and IPv6 loopback networks (`127.0.0.0/8`, `::1/128`).
+    pub fn loopback() -> [Self; 2] {
+        [
+            Self {
+                addr: IpAddr::V4([127, 0, 0, 0].into()),
+                prefix_len: 8,
+            },
+            Self {
+                addr: IpAddr::V6(1.into()),
+                prefix_len: 128,
+            },
+        ]
+    }
+
+    /// Whether `ip` is inside the network.
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) match IPv4 networks.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, canonical(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                u32::from(ip) & mask32(self.prefix_len) == u32::from(net)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                u128::from(ip) & mask128(self.prefix_len) == u128::from(net)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn mask32(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn mask128(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(IpAddr::V6(v6), IpAddr::V4),
+        v4 => v4,
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = IpNetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| IpNetParseError::InvalidAddress)?;
+        let prefix_len = match prefix_len {
+            Some(len) => len.parse().map_err(|_| IpNetParseError::InvalidPrefix)?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Self::new(addr, prefix_len)
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Error returned when parsing an [`IpNet`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpNetParseError {
+    /// The address part is not an IPv4 or IPv6 address.
+    InvalidAddress,
+    /// The prefix length is not a number or too long for the address family.
+    InvalidPrefix,
+}
+
+impl fmt::Display for IpNetParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAddress => write!(f, "invalid IP address"),
+            Self::InvalidPrefix => write!(f, "invalid network prefix length"),
+        }
+    }
+}
+
+impl std::error::Error for IpNetParseError {}
+
+/// Address of the client, resolved by [`IpAccessLayer`].
+///
+/// The peer address, or the address reported by a trusted proxy in the
+/// layer's [`ForwardedHeader`]. Inserted into the request extensions, and
+/// used for audit events and session logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Marks requests whose client address [`IpAccessLayer`] could not resolve,
+/// so the peer (a trusted proxy) is not mistaken for the client.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct UnresolvedClient;
+
+/// Header in which trusted proxies report the client address.
+///
+/// Only the selected header is read. Configure the proxy to set or append
+/// to that header; a header of the other kind is ignored, since it may have
+/// been sent by the client unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ForwardedHeader {
+    /// `X-Forwarded-For: client, proxy1, proxy2`.
+    #[default]
+    XForwardedFor,
+    /// RFC 7239 `Forwarded: for=client, for=proxy1`.
+    Forwarded,
+}
+
+#[derive(Clone, Default)]
+struct Rules {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    trusted_proxies: Vec<IpNet>,
+    forwarded_header: ForwardedHeader,
+}
+
+impl Rules {
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    fn is_allowed(&self, ip: IpAddr) -> bool {
+        !self.deny.iter().any(|net| net.contains(ip))
+            && (self.allow.is_empty() || self.allow.iter().any(|net| net.contains(ip)))
+    }
+
+    /// Client address behind the chain of trusted proxies ending at `peer`.
+    ///
+    /// `None` if a trusted proxy reported a hop without a usable address
+    /// (`unknown`, an obfuscated identifier or garbage): that hop is the
+    /// client, but its address is not known.
+    fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> Option<IpAddr> {
+        if !self.is_trusted(peer) {
+            return Some(canonical(peer));
+        }
+        let mut hops = forwarded_for(self.forwarded_header, headers);
+        // Walk from the closest hop; the first untrusted address is the client.
+        let mut client = peer;
+        while let Some(hop) = hops.pop() {
+            client = hop?;
+            if !self.is_trusted(client) {
+                break;
+            }
+        }
+        Some(canonical(client))
+    }
+}
+
+/// Hops listed in the `kind` header, in order from the original client to the last
+/// proxy. Hops without a parseable address are `None`.
+fn forwarded_for(kind: ForwardedHeader, headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let name = match kind {
+        ForwardedHeader::XForwardedFor => "x-forwarded-for",
+        ForwardedHeader::Forwarded => header::FORWARDED.as_str(),
+    };
+    let elements = headers
+        .get_all(name)
+        .iter()
+        .map(|value| value.to_str().unwrap_or_default())
+        .flat_map(|value| value.split(','));
+    match kind {
+        ForwardedHeader::XForwardedFor => elements.map(parse_node).collect(),
+        ForwardedHeader::Forwarded => elements
+            .map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.trim().split_once('=')?;
+                    key.eq_ignore_ascii_case("for").then(|| parse_node(value))
+                })?
+            })
+            .collect(),
+    }
+}
+
+/// Parse a `Forwarded` node (`192.0.2.1`, `"[2001:db8::1]:443"`, ...).
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.parse()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Layer that allows or denies clients by IP address.
+///
+/// The client address is the TCP peer (the server must be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`), unless the peer is
+/// a trusted proxy, in which case it is taken from the header selected with
+/// [`forwarded_header`](Self::forwarded_header) (`X-Forwarded-For` by
+/// default). Denied clients get `403 Forbidden`; allowed requests carry a
+/// [`ClientIp`] extension.
+///
+/// If a trusted proxy reports a hop without a usable address, the client is
+/// unresolved: it only passes a layer without allow or deny rules, and is
+/// never treated as coming from a trusted network.
+///
+/// Deny rules take precedence. Without allow rules every address not denied
+/// is allowed.
+///
+/// Add it after (outside) [`TokenAuthLayer`](super::TokenAuthLayer) so the
+/// auth layer sees the resolved address, e.g. to let loopback clients in
+/// without a token:
+///
+/// ```rust,ignore
+/// use mcp_core::auth::{IpAccessLayer, IpNet};
+/// use mcp_core::TokenAuthLayer;
+///
+/// let router = Router::new()
+///     .route("/sse", get(handler))
+///     .layer(TokenAuthLayer::new(token).trust_networks(IpNet::loopback()))
+///     .layer(
+///         IpAccessLayer::new()
+///             .allow("10.0.0.0/8".parse()?)
+///             .allow_all(IpNet::loopback())
+///             .trusted_proxy("10.0.0.2".parse()?),
+///     );
+/// ```
+#[derive(Clone, Default)]
+pub struct IpAccessLayer {
+    rules: Arc<Rules>,
+}
+
+impl IpAccessLayer {
+    /// Create a layer allowing every client.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow clients in `net`.
+    pub fn allow(self, net: IpNet) -> Self {
+        self.allow_all([net])
+    }
+
+    /// Allow clients in any of `nets`.
+    pub fn allow_all(mut self, nets: impl IntoIterator<Item = IpNet>) -> Self {
+        Arc::make_mut(&mut self.rules).allow.extend(nets);
+        self
+    }
+
+    /// Deny clients in `net`, even if an allow rule matches.
+    pub fn deny(mut self, net: IpNet) -> Self {
+        Arc::make_mut(&mut self.rules).deny.push(net);
+        self
+    }
+
+    /// Trust the [`ForwardedHeader`] sent by peers in `net`.
+    pub fn trusted_proxy(mut self, net: IpNet) -> Self {
+        Arc::make_mut(&mut self.rules).trusted_proxies.push(net);
+        self
+    }
+
+    /// Read client addresses reported by trusted proxies from `header`.
+    pub fn forwarded_header(mut self, header: ForwardedHeader) -> Self {
+        Arc::make_mut(&mut self.rules).forwarded_header = header;
+        self
+    }
+}
+
+impl<S> Layer<S> for IpAccessLayer {
+    type Service = IpAccessService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpAccessService {
+            inner,
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+/// Service that filters clients by IP address. See [`IpAccessLayer`].
+#[derive(Clone)]
+pub struct IpAccessService<S> {
+    inner: S,
+    rules: Arc<Rules>,
+}
+
+impl<S> Service<Request<Body>> for IpAccessService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let peer = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let client = peer.and_then(|peer| self.rules.client_ip(peer, req.headers()));
+
+        // Without a known address only a rule-free layer lets requests through.
+        let allowed = match client {
+            Some(ip) => self.rules.is_allowed(ip),
+            None => self.rules.allow.is_empty() && self.rules.deny.is_empty(),
+        };
+        if !allowed {
+            return Box::pin(async move {
+                let response = Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::from("Forbidden"))
+                    .unwrap();
+                Ok(response)
+            });
+        }
+
+        match client {
+            Some(ip) => {
+                req.extensions_mut().insert(ClientIp(ip));
+            }
+            None => {
+                req.extensions_mut().insert(UnresolvedClient);
+            }
+        }
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Extension, Router};
+    use tower::util::ServiceExt;
+
+    fn net(s: &str) -> IpNet {
+        s.parse().unwrap()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_matches_cidrs() {
+        assert!(net("10.0.0.0/8").contains(ip("10.20.30.40")));
+        assert!(!net("10.0.0.0/8").contains(ip("11.0.0.1")));
+        assert!(net("10.1.2.3/8").contains(ip("10.9.9.9")));
+        assert!(net("192.168.1.5").contains(ip("192.168.1.5")));
+        assert!(!net("192.168.1.5").contains(ip("192.168.1.6")));
+        assert!(net("0.0.0.0/0").contains(ip("8.8.8.8")));
+        assert!(net("2001:db8::/32").contains(ip("2001:db8::1")));
+        assert!(net("127.0.0.0/8").contains(ip("::ffff:127.0.0.1")));
+        assert!(!net("::/0").contains(ip("127.0.0.1")));
+        assert_eq!(net("10.1.2.3/8").to_string(), "10.0.0.0/8");
+
+        assert_eq!(
+            "10.0.0.0/33".parse::<IpNet>(),
+            Err(IpNetParseError::InvalidPrefix)
+        );
+        assert_eq!(
+            "example/8".parse::<IpNet>(),
+            Err(IpNetParseError::InvalidAddress)
+        );
+    }
+
+    #[test]
+    fn resolves_client_behind_trusted_proxies() {
+        let rules = Rules {
+            trusted_proxies: vec![net("10.0.0.0/8")],
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "203.0.113.9, 198.51.100.7, 10.0.0.3".parse().unwrap(),
+        );
+
+        // Spoofed entries left of the first untrusted hop are ignored.
+        assert_eq!(
+            rules.client_ip(ip("10.0.0.2"), &headers),
+            Some(ip("198.51.100.7"))
+        );
+        // Headers from untrusted peers are ignored.
+        assert_eq!(
+            rules.client_ip(ip("192.0.2.1"), &headers),
+            Some(ip("192.0.2.1"))
+        );
+        assert_eq!(
+            rules.client_ip(ip("10.0.0.2"), &HeaderMap::new()),
+            Some(ip("10.0.0.2"))
+        );
+
+        // Only the configured header is read.
+        headers.insert(header::FORWARDED, "for=127.0.0.1".parse().unwrap());
+        assert_eq!(
+            rules.client_ip(ip("10.0.0.2"), &headers),
+            Some(ip("198.51.100.7"))
+        );
+        let forwarded = Rules {
+            forwarded_header: ForwardedHeader::Forwarded,
+            ..rules.clone()
+        };
+        headers.insert(
+            header::FORWARDED,
+            r#"for=192.0.2.60;proto=https, for="[2001:db8::7]:4711""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            forwarded.client_ip(ip("10.0.0.2"), &headers),
+            Some(ip("2001:db8::7"))
+        );
+    }
+
+    #[test]
+    fn stops_at_unknown_hops() {
+        let rules = Rules {
+            trusted_proxies: vec![net("10.0.0.0/8"), net("127.0.0.1")],
+            forwarded_header: ForwardedHeader::Forwarded,
+            ..Default::default()
+        };
+        let client_ip = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::FORWARDED, value.parse().unwrap());
+            rules.client_ip(ip("10.0.0.2"), &headers)
+        };
+        // The client cannot hide behind an unparseable hop to pick an address.
+        assert_eq!(client_ip("for=127.0.0.1, for=unknown"), None);
+        assert_eq!(client_ip("for=127.0.0.1, for=_hidden, for=10.0.0.3"), None);
+        assert_eq!(client_ip("for=127.0.0.1, proto=https"), None);
+        assert_eq!(
+            client_ip("for=unknown, for=192.0.2.60"),
+            Some(ip("192.0.2.60"))
+        );
+    }
+
+    async fn request(router: &Router, peer: &str, forwarded_for: Option<&str>) -> Response {
+        let mut request = Request::builder().uri("/ip");
+        if let Some(forwarded_for) = forwarded_for {
+            request = request.header("x-forwarded-for", forwarded_for);
+        }
+        let mut request = request.body(Body::empty()).unwrap();
+        let peer = SocketAddr::new(ip(peer), 40000);
+        request.extensions_mut().insert(ConnectInfo(peer));
+        router.clone().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn filters_clients_and_records_client_ip() {
+        async fn client_ip(Extension(ClientIp(ip)): Extension<ClientIp>) -> String {
+            ip.to_string()
+        }
+
+        let router = Router::new().route("/ip", get(client_ip)).layer(
+            IpAccessLayer::new()
+                .allow(net("203.0.113.0/24"))
+                .allow_all(IpNet::loopback())
+                .deny(net("203.0.113.66"))
+                .trusted_proxy(net("127.0.0.1")),
+        );
+
+        let response = request(&router, "127.0.0.1", Some("203.0.113.5")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"203.0.113.5");
+
+        let response = request(&router, "127.0.0.1", Some("203.0.113.66")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = request(&router, "127.0.0.1", Some("198.51.100.1")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        // An unresolved client is not mistaken for the loopback proxy.
+        let response = request(&router, "127.0.0.1", Some("unknown")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = request(&router, "198.51.100.1", None).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = request(&router, "::1", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -13,39 +13,148 @@ use std::{
 };
 use tower::{Layer, Service};
 
+use super::policy::AccessPolicy;
+use super::principal::{Principal, ALL_SCOPES, DEFAULT_PRINCIPAL};
+use super::tokens::{TokenEntry, TokenStore};
+#[cfg(feature = "audit")]
+use crate::audit::{AuditEvent, AuditEventKind, AuditSink, AuthFailure};
+
 /// Layer that adds token authentication to a service.
 ///
+/// Every request needs a valid token unless its path is marked public.
+/// Route prefixes can additionally require a scope, which the token's
+/// [`Principal`] must have been granted; requests without it get `403`.
+///
 /// # Example
 ///
 /// ```rust,ignore
-/// use mcp_core::TokenAuthLayer;
+/// use mcp_core::{Principal, TokenAuthLayer};
 ///
 /// let router = Router::new()
 ///     .route("/api", get(handler))
-///     .layer(TokenAuthLayer::new("my-secret-token".to_string()));
+///     .route("/admin/log-level", get(log_level))
+///     .route("/healthz", get(healthz))
+///     .layer(
+///         TokenAuthLayer::new("my-secret-token".to_string())
+///             .with_token("read-only-token".to_string(), Principal::new("ci").with_scopes(["read"]))
+///             .public("/healthz")
+///             .public("/static/*")
+///             .require_scope("/api", "read")
+///             .require_scope("/admin", "admin"),
+///     );
 /// ```
+///
+/// Use [`with_store`](Self::with_store) for tokens that expire, rotate or are
+/// reloaded from a file.
 #[derive(Clone)]
 pub struct TokenAuthLayer {
-    token: Arc<str>,
+    tokens: TokenStore,
     realm: Arc<str>,
+    policy: Arc<AccessPolicy>,
+    #[cfg(feature = "audit")]
+    audit: Option<Arc<dyn AuditSink>>,
 }
 
 impl TokenAuthLayer {
     /// Create a new token auth layer with the given token.
+    ///
+    /// The token authenticates the `"default"` principal, which has every
+    /// scope.
     pub fn new(token: String) -> Self {
-        Self {
-            token: Arc::from(token),
-            realm: Arc::from("mcp-core"),
-        }
+        Self::with_realm(token, "mcp-core".to_string())
     }
 
     /// Create a new token auth layer with a custom realm.
     pub fn with_realm(token: String, realm: String) -> Self {
+        let principal = Principal::new(DEFAULT_PRINCIPAL).with_scopes([ALL_SCOPES]);
+        Self::from_tokens([(token, principal)]).realm(realm)
+    }
+
+    /// Create a token auth layer accepting each token as its principal.
+    pub fn from_tokens<I>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Principal)>,
+    {
+        Self::with_store(TokenStore::new(
+            tokens
+                .into_iter()
+                .map(|(token, principal)| TokenEntry::new(token, principal)),
+        ))
+    }
+
+    /// Create a token auth layer accepting the tokens of `store`.
+    ///
+    /// Changes to the store apply to requests immediately.
+    pub fn with_store(tokens: TokenStore) -> Self {
         Self {
-            token: Arc::from(token),
-            realm: Arc::from(realm),
+            tokens,
+            realm: Arc::from("mcp-core"),
+            policy: Arc::default(),
+            #[cfg(feature = "audit")]
+            audit: None,
         }
     }
+
+    /// Set the realm sent in `WWW-Authenticate` challenges.
+    pub fn realm(mut self, realm: String) -> Self {
+        self.realm = Arc::from(realm);
+        self
+    }
+
+    /// Also accept `token`, authenticating it as `principal`.
+    pub fn with_token(self, token: String, principal: Principal) -> Self {
+        self.tokens.insert(TokenEntry::new(token, principal));
+        self
+    }
+
+    /// Serve paths matching `pattern` without authentication.
+    ///
+    /// The pattern is an exact path, or a prefix when it ends with `*`
+    /// (e.g. `"/static/*"`). Prefixes match whole path segments, so
+    /// `"/health*"` covers `/health` and `/health/live` but not
+    /// `/healthcheck-admin`. Valid credentials sent to a public path still
+    /// attach a [`Principal`].
+    pub fn public(mut self, pattern: &str) -> Self {
+        Arc::make_mut(&mut self.policy).add_public(pattern);
+        self
+    }
+
+    /// Serve requests for which `predicate` returns `true` without
+    /// authentication.
+    pub fn public_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Request<Body>) -> bool + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.policy).add_public_if(Arc::new(predicate));
+        self
+    }
+
+    /// Serve clients in `nets` without authentication, e.g.
+    /// [`IpNet::loopback`](super::IpNet::loopback).
+    ///
+    /// The client address is the [`ClientIp`](super::ClientIp) resolved by an
+    /// outer [`IpAccessLayer`](super::IpAccessLayer), or else the TCP peer.
+    pub fn trust_networks(mut self, nets: impl IntoIterator<Item = super::IpNet>) -> Self {
+        Arc::make_mut(&mut self.policy).add_trusted(nets);
+        self
+    }
+
+    /// Require `scope` for paths under `prefix`.
+    ///
+    /// Prefixes match whole path segments, and when several match the
+    /// longest wins, so `require_scope("/", "read")` can be combined with a
+    /// stricter `require_scope("/admin", "admin")`.
+    pub fn require_scope(mut self, prefix: &str, scope: &str) -> Self {
+        Arc::make_mut(&mut self.policy).add_scope(prefix, scope);
+        self
+    }
+
+    /// Record rejected authentication attempts to the given audit sink.
+    #[cfg(feature = "audit")]
+    pub fn with_audit(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
 }
 
 impl<S> Layer<S> for TokenAuthLayer {
@@ -54,8 +163,11 @@ impl<S> Layer<S> for TokenAuthLayer {
     fn layer(&self, inner: S) -> Self::Service {
         TokenAuthService {
             inner,
-            token: self.token.clone(),
+            tokens: self.tokens.clone(),
             realm: self.realm.clone(),
+            policy: self.policy.clone(),
+            #[cfg(feature = "audit")]
+            audit: self.audit.clone(),
         }
     }
 }
@@ -65,11 +177,78 @@ impl<S> Layer<S> for TokenAuthLayer {
 /// Accepts authentication via:
 /// - Bearer token: `Authorization: Bearer <token>`
 /// - Basic Auth: Any username with token as password
+///
+/// Authenticated requests carry a [`Principal`] extension.
 #[derive(Clone)]
 pub struct TokenAuthService<S> {
     inner: S,
-    token: Arc<str>,
+    tokens: TokenStore,
     realm: Arc<str>,
+    policy: Arc<AccessPolicy>,
+    #[cfg(feature = "audit")]
+    audit: Option<Arc<dyn AuditSink>>,
+}
+
+/// Why a request was not authenticated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rejection {
+    Missing,
+    Malformed,
+    Invalid,
+    MissingScope,
+}
+
+#[cfg(feature = "audit")]
+impl From<Rejection> for AuthFailure {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::Missing => Self::MissingCredentials,
+            Rejection::Malformed => Self::MalformedCredentials,
+            Rejection::Invalid => Self::InvalidToken,
+            Rejection::MissingScope => Self::MissingScope,
+        }
+    }
+}
+
+/// Check the `Authorization` header against the known tokens.
+fn authenticate(req: &Request<Body>, tokens: &TokenStore) -> Result<Principal, Rejection> {
+    let auth_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .ok_or(Rejection::Missing)?;
+    let auth_str = auth_header.to_str().map_err(|_| Rejection::Malformed)?;
+
+    // Check Bearer token
+    if let Some(bearer_token) = auth_str.strip_prefix("Bearer ") {
+        return tokens.authenticate(bearer_token).ok_or(Rejection::Invalid);
+    }
+
+    // Check Basic Auth (any username, token as password)
+    if let Some(basic_creds) = auth_str.strip_prefix("Basic ") {
+        let decoded = base64_decode(basic_creds).map_err(|_| Rejection::Malformed)?;
+        let (_username, password) = decoded.split_once(':').ok_or(Rejection::Malformed)?;
+        return tokens.authenticate(password).ok_or(Rejection::Invalid);
+    }
+
+    Err(Rejection::Malformed)
+}
+
+impl<S> TokenAuthService<S> {
+    #[cfg(feature = "audit")]
+    fn audit_rejection(&self, req: &Request<Body>, rejection: Rejection) {
+        if let Some(audit) = &self.audit {
+            audit.record(
+                &AuditEvent::new(AuditEventKind::AuthFailed {
+                    reason: rejection.into(),
+                    path: req.uri().path().to_string(),
+                })
+                .remote_addr(super::remote_ip(req.extensions())),
+            );
+        }
+    }
+
+    #[cfg(not(feature = "audit"))]
+    fn audit_rejection(&self, _req: &Request<Body>, _rejection: Rejection) {}
 }
 
 impl<S> Service<Request<Body>> for TokenAuthService<S>
@@ -85,33 +264,36 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, req: Request<Body>) -> Self::Future {
-        let token = self.token.clone();
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
         let realm = self.realm.clone();
         let mut inner = self.inner.clone();
 
-        Box::pin(async move {
-            // Check Authorization header
-            if let Some(auth_header) = req.headers().get(header::AUTHORIZATION) {
-                if let Ok(auth_str) = auth_header.to_str() {
-                    // Check Bearer token
-                    if let Some(bearer_token) = auth_str.strip_prefix("Bearer ") {
-                        if bearer_token == token.as_ref() {
-                            return inner.call(req).await;
-                        }
-                    }
-
-                    // Check Basic Auth (any username, token as password)
-                    if let Some(basic_creds) = auth_str.strip_prefix("Basic ") {
-                        if let Ok(decoded) = base64_decode(basic_creds) {
-                            if let Some((_username, password)) = decoded.split_once(':') {
-                                if password == token.as_ref() {
-                                    return inner.call(req).await;
-                                }
-                            }
-                        }
-                    }
+        if self.policy.is_public(&req) {
+            if let Ok(principal) = authenticate(&req, &self.tokens) {
+                req.extensions_mut().insert(principal);
+            }
+            return Box::pin(async move { inner.call(req).await });
+        }
+
+        let rejection = match authenticate(&req, &self.tokens) {
+            Ok(principal) => match self.policy.required_scope(req.uri().path()) {
+                Some(scope) if !principal.has_scope(scope) => Rejection::MissingScope,
+                _ => {
+                    req.extensions_mut().insert(principal);
+                    return Box::pin(async move { inner.call(req).await });
                 }
+            },
+            Err(rejection) => rejection,
+        };
+        self.audit_rejection(&req, rejection);
+
+        Box::pin(async move {
+            if rejection == Rejection::MissingScope {
+                let response = Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::from("Forbidden"))
+                    .unwrap();
+                return Ok(response);
             }
 
             // No valid auth - return 401
@@ -232,6 +414,168 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 
+    #[tokio::test]
+    async fn test_principal_extension() {
+        async fn whoami(axum::Extension(principal): axum::Extension<Principal>) -> String {
+            principal.name().to_string()
+        }
+
+        let app = Router::new()
+            .route("/whoami", get(whoami))
+            .layer(TokenAuthLayer::new("secret123".to_string()));
+
+        let request = Request::builder()
+            .uri("/whoami")
+            .header("Authorization", "Bearer secret123")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], DEFAULT_PRINCIPAL.as_bytes());
+    }
+
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn test_audits_rejections() {
+        use crate::audit::MemoryAuditSink;
+
+        let sink = Arc::new(MemoryAuditSink::new());
+        let app = Router::new()
+            .route("/test", get(test_handler))
+            .layer(TokenAuthLayer::new("secret123".to_string()).with_audit(sink.clone()));
+
+        for auth in [None, Some("Bearer wrong"), Some("Digest abc")] {
+            let mut request = Request::builder().uri("/test");
+            if let Some(auth) = auth {
+                request = request.header("Authorization", auth);
+            }
+            let request = request.body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let reasons: Vec<_> = sink
+            .events()
+            .into_iter()
+            .map(|event| match event.kind {
+                AuditEventKind::AuthFailed { reason, path } => {
+                    assert_eq!(path, "/test");
+                    reason
+                }
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                AuthFailure::MissingCredentials,
+                AuthFailure::InvalidToken,
+                AuthFailure::MalformedCredentials,
+            ]
+        );
+    }
+
+    async fn status(app: &Router, uri: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let request = request.body(Body::empty()).unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_public_routes() {
+        let app = Router::new()
+            .route("/healthz", get(test_handler))
+            .route("/static/app.js", get(test_handler))
+            .route("/favicon.ico", get(test_handler))
+            .route("/test", get(test_handler))
+            .layer(
+                TokenAuthLayer::new("secret123".to_string())
+                    .public("/healthz")
+                    .public("/static/*")
+                    .public_if(|req| req.uri().path() == "/favicon.ico"),
+            );
+
+        assert_eq!(status(&app, "/healthz", None).await, StatusCode::OK);
+        assert_eq!(status(&app, "/static/app.js", None).await, StatusCode::OK);
+        assert_eq!(status(&app, "/favicon.ico", None).await, StatusCode::OK);
+        assert_eq!(status(&app, "/test", None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_required_scopes() {
+        let app = Router::new()
+            .route("/healthz", get(test_handler))
+            .route("/tools", get(test_handler))
+            .route("/admin/log-level", get(test_handler))
+            .layer(
+                TokenAuthLayer::new("admin-token".to_string())
+                    .with_token(
+                        "reader-token".to_string(),
+                        Principal::new("reader").with_scopes(["read"]),
+                    )
+                    .public("/healthz")
+                    .require_scope("/", "read")
+                    .require_scope("/admin", "admin"),
+            );
+
+        assert_eq!(status(&app, "/healthz", None).await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "/tools", Some("reader-token")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "/admin/log-level", Some("reader-token")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&app, "/admin/log-level", Some("admin-token")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "/admin/log-level", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trusted_networks() {
+        use super::super::ip::UnresolvedClient;
+        use super::super::{ClientIp, IpNet};
+        use axum::extract::ConnectInfo;
+        use std::net::SocketAddr;
+
+        let app = Router::new()
+            .route("/test", get(test_handler))
+            .layer(TokenAuthLayer::new("secret123".to_string()).trust_networks(IpNet::loopback()));
+
+        for (ip, expected) in [
+            ("127.0.0.1", StatusCode::OK),
+            ("::1", StatusCode::OK),
+            ("192.0.2.1", StatusCode::UNAUTHORIZED),
+        ] {
+            let mut request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+            request
+                .extensions_mut()
+                .insert(ClientIp(ip.parse().unwrap()));
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), expected, "{ip}");
+        }
+
+        // A client the IP layer could not resolve behind a loopback proxy.
+        let mut request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+        request.extensions_mut().insert(UnresolvedClient);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_custom_realm() {
         let app =
@@ -1,7 +1,21 @@
 //! Token-based authentication middleware.
 //!
-//! Supports both Bearer token and Basic Auth (with token as password).
+//! Supports both Bearer token and Basic Auth (with token as password), public
+//! routes and per-prefix scope requirements, plus an IP allowlist layer that
+//! resolves client addresses behind trusted proxies.
 
+mod hash;
+mod ip;
 mod middleware;
+mod policy;
+mod principal;
+mod tokens;
 
+#[cfg(feature = "argon2")]
+pub use hash::hash_token_argon2;
+pub use hash::{hash_token, TokenHashError};
+pub use ip::{ClientIp, ForwardedHeader, IpAccessLayer, IpAccessService, IpNet, IpNetParseError};
 pub use middleware::{TokenAuthLayer, TokenAuthService};
+pub(crate) use principal::remote_ip;
+pub use principal::Principal;
+pub use tokens::{TokenEntry, TokenFileError, TokenFileWatcher, TokenStore};
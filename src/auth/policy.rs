@@ -0,0 +1,142 @@
+//! Per-route access rules for [`TokenAuthLayer`](super::TokenAuthLayer).
+
+use std::sync::Arc;
+
+use axum::{body::Body, http::Request};
+
+use super::ip::IpNet;
+
+type Predicate = Arc<dyn Fn(&Request<Body>) -> bool + Send + Sync>;
+
+/// A path matched exactly, or by prefix when written with a trailing `*`.
+///
+/// Prefixes match whole path segments like scope prefixes; one ending in `/`
+/// matches the paths below it.
+#[derive(Clone, Debug)]
+enum PathPattern {
+    Exact(String),
+    Prefix(String),
+}
+
+impl PathPattern {
+    fn parse(pattern: &str) -> Self {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => Self::Prefix(prefix.to_string()),
+            None => Self::Exact(pattern.to_string()),
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::Exact(exact) => path == exact,
+            Self::Prefix(prefix) if prefix.ends_with('/') => path.starts_with(prefix.as_str()),
+            Self::Prefix(prefix) => covers(prefix, path),
+        }
+    }
+}
+
+/// Which requests skip authentication and which scopes the rest require.
+#[derive(Clone, Default)]
+pub(crate) struct AccessPolicy {
+    public: Vec<PathPattern>,
+    public_if: Vec<Predicate>,
+    trusted: Vec<IpNet>,
+    scopes: Vec<(String, String)>,
+}
+
+impl AccessPolicy {
+    pub(crate) fn add_public(&mut self, pattern: &str) {
+        self.public.push(PathPattern::parse(pattern));
+    }
+
+    pub(crate) fn add_public_if(&mut self, predicate: Predicate) {
+        self.public_if.push(predicate);
+    }
+
+    pub(crate) fn add_trusted(&mut self, nets: impl IntoIterator<Item = IpNet>) {
+        self.trusted.extend(nets);
+    }
+
+    pub(crate) fn add_scope(&mut self, prefix: &str, scope: &str) {
+        let prefix = prefix.trim_end_matches('/');
+        self.scopes.push((prefix.to_string(), scope.to_string()));
+    }
+
+    /// Whether `req` may be served without credentials.
+    pub(crate) fn is_public(&self, req: &Request<Body>) -> bool {
+        let path = req.uri().path();
+        self.public.iter().any(|pattern| pattern.matches(path))
+            || self.public_if.iter().any(|predicate| predicate(req))
+            || super::remote_ip(req.extensions())
+                .is_some_and(|ip| self.trusted.iter().any(|net| net.contains(ip)))
+    }
+
+    /// Scope required for `path`, from the longest matching prefix rule.
+    ///
+    /// Prefixes match whole path segments: `/admin` covers `/admin` and
+    /// `/admin/users` but not `/administrator`.
+    pub(crate) fn required_scope(&self, path: &str) -> Option<&str> {
+        self.scopes
+            .iter()
+            .filter(|(prefix, _)| covers(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, scope)| scope.as_str())
+    }
+}
+
+fn covers(prefix: &str, path: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.is_empty(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str) -> Request<Body> {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn matches_public_patterns() {
+        let mut policy = AccessPolicy::default();
+        policy.add_public("/healthz");
+        policy.add_public("/static/*");
+        policy.add_public_if(Arc::new(|req| req.uri().path().ends_with(".ico")));
+
+        assert!(policy.is_public(&request("/healthz")));
+        assert!(!policy.is_public(&request("/healthz/extra")));
+        assert!(policy.is_public(&request("/static/app.js")));
+        assert!(!policy.is_public(&request("/static")));
+        assert!(policy.is_public(&request("/favicon.ico")));
+        assert!(!policy.is_public(&request("/sse")));
+    }
+
+    #[test]
+    fn public_prefixes_match_whole_segments() {
+        let mut policy = AccessPolicy::default();
+        policy.add_public("/health*");
+
+        assert!(policy.is_public(&request("/health")));
+        assert!(policy.is_public(&request("/health/live")));
+        assert!(!policy.is_public(&request("/healthcheck-admin")));
+        assert!(!policy.is_public(&request("/sse")));
+    }
+
+    #[test]
+    fn longest_scope_prefix_wins() {
+        let mut policy = AccessPolicy::default();
+        policy.add_scope("/", "read");
+        policy.add_scope("/admin/", "admin");
+        policy.add_scope("/admin/log-level", "logs");
+
+        assert_eq!(policy.required_scope("/sse"), Some("read"));
+        assert_eq!(policy.required_scope("/admin"), Some("admin"));
+        assert_eq!(policy.required_scope("/admin/sessions"), Some("admin"));
+        assert_eq!(policy.required_scope("/administrator"), Some("read"));
+        assert_eq!(policy.required_scope("/admin/log-level"), Some("logs"));
+        assert_eq!(AccessPolicy::default().required_scope("/sse"), None);
+    }
+}
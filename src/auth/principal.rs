@@ -0,0 +1,104 @@
+//! Identity of authenticated clients.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::{extract::ConnectInfo, http::Extensions};
+
+use super::ip::{ClientIp, UnresolvedClient};
+
+/// Name of the principal authenticated by the single token of
+/// [`TokenAuthLayer::new`](super::TokenAuthLayer::new).
+pub(crate) const DEFAULT_PRINCIPAL: &str = "default";
+
+/// Scope granting every other scope.
+pub(crate) const ALL_SCOPES: &str = "*";
+
+/// An authenticated client.
+///
+/// [`TokenAuthService`](super::TokenAuthService) inserts it into the request
+/// extensions once the credentials are accepted, so handlers can extract it
+/// with `Extension<Principal>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    name: Arc<str>,
+    scopes: Arc<[String]>,
+}
+
+impl Principal {
+    /// Create a principal with the given name and no scopes.
+    pub fn new(name: impl Into<Arc<str>>) -> Self {
+        Self {
+            name: name.into(),
+            scopes: Arc::from([]),
+        }
+    }
+
+    /// Grant the given scopes.
+    ///
+    /// `"*"` grants every scope and a trailing `*` grants every scope with
+    /// that prefix, e.g. `"tool:*"`.
+    pub fn with_scopes<I, T>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The principal's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The scopes granted to the principal.
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Whether the principal was granted `scope`.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes
+            .iter()
+            .any(|granted| match granted.strip_suffix('*') {
+                Some(prefix) => scope.starts_with(prefix),
+                None => granted == scope,
+            })
+    }
+}
+
+/// Address of the client: the [`ClientIp`] resolved by
+/// [`IpAccessLayer`](super::IpAccessLayer), or else the connected peer if the
+/// server was started with `into_make_service_with_connect_info::<SocketAddr>()`.
+///
+/// `None` if the layer could not resolve the client behind a trusted proxy.
+pub(crate) fn remote_ip(extensions: &Extensions) -> Option<IpAddr> {
+    if let Some(ClientIp(ip)) = extensions.get::<ClientIp>() {
+        return Some(*ip);
+    }
+    if extensions.get::<UnresolvedClient>().is_some() {
+        return None;
+    }
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_and_wildcard_scopes() {
+        let principal = Principal::new("ci").with_scopes(["read", "tool:*"]);
+        assert!(principal.has_scope("read"));
+        assert!(!principal.has_scope("admin"));
+        assert!(principal.has_scope("tool:search"));
+        assert!(!principal.has_scope("prompt:search"));
+
+        let admin = Principal::new("admin").with_scopes([ALL_SCOPES]);
+        assert!(admin.has_scope("admin"));
+        assert!(!Principal::new("nobody").has_scope("read"));
+    }
+}
@@ -0,0 +1,584 @@
+//! Reloadable set of accepted tokens with validity windows.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, RwLock},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use super::hash::{TokenHashError, TokenSecret};
+use super::principal::Principal;
+#[cfg(feature = "config")]
+use super::principal::{ALL_SCOPES, DEFAULT_PRINCIPAL};
+#[cfg(feature = "config")]
+use crate::config::{BaseConfig, Secret};
+use crate::timestamp::parse_rfc3339;
+
+/// A token and the principal it authenticates, optionally limited in time.
+#[derive(Clone)]
+pub struct TokenEntry {
+    secret: TokenSecret,
+    principal: Principal,
+    not_before: Option<SystemTime>,
+    expires_at: Option<SystemTime>,
+    origin: Origin,
+}
+
+/// Where an entry came from, so that reloading a token file or the config
+/// only replaces the entries it added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Origin {
+    /// Added by the application.
+    Added,
+    /// Read from a token file.
+    File,
+    /// Derived from [`BaseConfig`].
+    #[cfg(feature = "config")]
+    Config,
+}
+
+impl TokenEntry {
+    /// Accept `token` as `principal` without time limits.
+    pub fn new(token: impl AsRef<str>, principal: Principal) -> Self {
+        Self::with_secret(TokenSecret::plain(token.as_ref()), principal)
+    }
+
+    /// Accept the token hashed as `hash` (see [`hash_token`](super::hash_token)).
+    pub fn hashed(hash: &str, principal: Principal) -> Result<Self, TokenHashError> {
+        if !hash.starts_with("sha256$") && !hash.starts_with("$argon2") {
+            return Err(TokenHashError::Malformed);
+        }
+        Ok(Self::with_secret(TokenSecret::parse(hash)?, principal))
+    }
+
+    fn with_secret(secret: TokenSecret, principal: Principal) -> Self {
+        Self {
+            secret,
+            principal,
+            not_before: None,
+            expires_at: None,
+            origin: Origin::Added,
+        }
+    }
+
+    #[cfg(feature = "config")]
+    fn origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Let the token expire at `deadline` unless it expires earlier.
+    fn retire(&mut self, deadline: SystemTime) {
+        self.expires_at = Some(self.expires_at.map_or(deadline, |exp| exp.min(deadline)));
+    }
+
+    /// Reject the token before `time`.
+    pub fn not_before(mut self, time: SystemTime) -> Self {
+        self.not_before = Some(time);
+        self
+    }
+
+    /// Reject the token from `time` on.
+    pub fn expires_at(mut self, time: SystemTime) -> Self {
+        self.expires_at = Some(time);
+        self
+    }
+
+    /// The principal the token authenticates.
+    pub fn principal(&self) -> &Principal {
+        &self.principal
+    }
+
+    /// Whether the token is valid at `now`.
+    pub fn is_valid_at(&self, now: SystemTime) -> bool {
+        !matches!(self.not_before, Some(nbf) if now < nbf)
+            && !matches!(self.expires_at, Some(exp) if now >= exp)
+    }
+}
+
+impl fmt::Debug for TokenEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenEntry")
+            .field("secret", &"[REDACTED]")
+            .field("principal", &self.principal)
+            .field("not_before", &self.not_before)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Tokens accepted by [`TokenAuthLayer`](super::TokenAuthLayer).
+///
+/// Cloning is cheap and clones share the same set, so tokens can be replaced,
+/// rotated or reloaded from a file while the server is running.
+///
+/// # Token file
+///
+/// One token per line, followed by the principal name and optional
+/// `scopes=`, `not_before=` and `expires=` attributes (timestamps in RFC 3339,
+/// UTC). Blank lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # token                          name   attributes
+/// 3f1c9a0e6b2d4f58a7c1e9b0d3f6a2c4 admin  scopes=*
+/// 9b2e7d1c4a6f3e8b0c5d2a7f1e4b6c9d ci     scopes=read,tool:* expires=2026-11-01T00:00:00Z
+/// 5e8a1f3c7b0d2e6a9c4f1b8d3e7a0c2f ci     scopes=read,tool:* not_before=2026-10-25T00:00:00Z
+/// ```
+///
+/// Listing an old and a new token for the same principal with overlapping
+/// validity lets clients switch at their own pace.
+///
+/// Instead of the plaintext token, a line can start with its hash as produced
+/// by [`hash_token`](super::hash_token) (or `hash_token_argon2` with the
+/// `argon2` feature). Set the pepper used for hashing with
+/// [`with_pepper`](Self::with_pepper).
+#[derive(Clone, Default)]
+pub struct TokenStore {
+    entries: Arc<RwLock<Arc<Vec<TokenEntry>>>>,
+    pepper: Arc<[u8]>,
+}
+
+impl TokenStore {
+    /// Create a store accepting `entries`.
+    pub fn new(entries: impl IntoIterator<Item = TokenEntry>) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(Arc::new(entries.into_iter().collect()))),
+            pepper: Arc::from([]),
+        }
+    }
+
+    /// Verify hashed tokens with `pepper`, a server-side secret kept out of
+    /// the token file.
+    pub fn with_pepper(mut self, pepper: impl AsRef<[u8]>) -> Self {
+        self.pepper = Arc::from(pepper.as_ref());
+        self
+    }
+
+    /// Load a store from a token file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TokenFileError> {
+        Ok(Self::new(parse_file(path.as_ref())?))
+    }
+
+    fn snapshot(&self) -> Arc<Vec<TokenEntry>> {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<TokenEntry>)) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        f(Arc::make_mut(&mut entries));
+    }
+
+    /// The current entries, including expired and not yet valid ones.
+    pub fn entries(&self) -> Vec<TokenEntry> {
+        self.snapshot().to_vec()
+    }
+
+    /// Accept an additional token.
+    pub fn insert(&self, entry: TokenEntry) {
+        self.update(|entries| entries.push(entry));
+    }
+
+    /// Replace all tokens.
+    pub fn replace(&self, entries: impl IntoIterator<Item = TokenEntry>) {
+        *self.entries.write().unwrap_or_else(|e| e.into_inner()) =
+            Arc::new(entries.into_iter().collect());
+    }
+
+    /// Replace the tokens read from a token file with the contents of
+    /// `path`.
+    ///
+    /// Tokens added with [`insert`](Self::insert), [`rotate`](Self::rotate)
+    /// or from the config are kept. On error the current tokens are kept.
+    pub fn reload(&self, path: impl AsRef<Path>) -> Result<(), TokenFileError> {
+        let loaded = parse_file(path.as_ref())?;
+        self.update(|entries| {
+            entries.retain(|entry| entry.origin != Origin::File);
+            entries.extend(loaded);
+        });
+        Ok(())
+    }
+
+    /// Accept `token` for `principal` and let its current tokens expire after
+    /// `grace`, so old and new token both work in the meantime.
+    pub fn rotate(&self, token: impl AsRef<str>, principal: Principal, grace: Duration) {
+        let deadline = SystemTime::now() + grace;
+        let entry = TokenEntry::new(token, principal);
+        self.update(|entries| {
+            for old in entries
+                .iter_mut()
+                .filter(|old| old.principal.name() == entry.principal.name())
+            {
+                old.retire(deadline);
+            }
+            entries.push(entry);
+        });
+    }
+
+    /// The principal of `token` if it is currently valid.
+    pub(crate) fn authenticate(&self, token: &str) -> Option<Principal> {
+        let now = SystemTime::now();
+        self.snapshot()
+            .iter()
+            .filter(|entry| entry.is_valid_at(now))
+            .find(|entry| entry.secret.verify(token, &self.pepper))
+            .map(|entry| entry.principal.clone())
+    }
+
+    /// Reload the store from `path` whenever the file changes.
+    ///
+    /// The file is checked every `interval` on a background thread, which
+    /// stops when the returned watcher is dropped. Files that fail to parse
+    /// are logged and leave the current tokens in place.
+    pub fn watch_file(
+        &self,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Result<TokenFileWatcher, TokenFileError> {
+        let path = path.into();
+        self.reload(&path)?;
+        let mut last = modified(&path);
+
+        let store = self.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        thread::Builder::new()
+            .name("token-file-watcher".to_string())
+            .spawn(move || {
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let current = modified(&path);
+                    if current == last {
+                        continue;
+                    }
+                    last = current;
+                    let file = path.display();
+                    match store.reload(&path) {
+                        Ok(()) => tracing::info!(path = %file, "reloaded token file"),
+                        Err(e) => {
+                            tracing::error!(path = %file, error = %e, "failed to reload token file");
+                        }
+                    }
+                }
+            })
+            .map_err(TokenFileError::Io)?;
+
+        Ok(TokenFileWatcher { _stop: stop })
+    }
+}
+
+#[cfg(feature = "config")]
+impl TokenStore {
+    /// Create a store accepting the token of `config`.
+    ///
+    /// [`auth_token`](BaseConfig::auth_token) and
+    /// [`auth_token_hash`](BaseConfig::auth_token_hash) authenticate the
+    /// default principal with all scopes; hashes are verified with
+    /// [`auth_token_pepper`](BaseConfig::auth_token_pepper).
+    pub fn from_config(config: &BaseConfig) -> Result<Self, TokenHashError> {
+        let pepper = config.auth_token_pepper.as_ref().map_or("", Secret::expose);
+        Ok(Self::new(config_entries(config)?).with_pepper(pepper))
+    }
+
+    /// Apply the token of each config published by `config`, as returned by
+    /// [`watch_config`](crate::config::watch_config).
+    ///
+    /// Only the tokens taken from the config are replaced; tokens added
+    /// otherwise, e.g. with [`insert`](Self::insert) or
+    /// [`watch_file`](Self::watch_file), are kept. When the configured token
+    /// changes, the old one keeps working for `grace`, as with
+    /// [`rotate`](Self::rotate), so clients can switch over.
+    ///
+    /// The task ends when the watcher stops. Configs with a malformed token
+    /// hash are logged and leave the current tokens in place.
+    #[cfg(feature = "reload")]
+    pub fn follow_config(
+        &self,
+        mut config: tokio::sync::watch::Receiver<Arc<BaseConfig>>,
+        grace: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            while config.changed().await.is_ok() {
+                let entries = config_entries(&config.borrow_and_update());
+                match entries {
+                    Ok(entries) => store.rotate_config_entries(entries, grace),
+                    Err(e) => tracing::error!(error = %e, "failed to apply token from config"),
+                }
+            }
+        })
+    }
+
+    /// Swap the config tokens for `configured`, letting the ones no longer
+    /// configured expire after `grace`.
+    #[cfg(feature = "reload")]
+    fn rotate_config_entries(&self, configured: Vec<TokenEntry>, grace: Duration) {
+        let now = SystemTime::now();
+        let deadline = now + grace;
+        self.update(|entries| {
+            entries.retain_mut(|old| {
+                if old.origin != Origin::Config {
+                    return true;
+                }
+                // Still configured: replaced by the new entry below.
+                if configured.iter().any(|entry| entry.secret == old.secret) {
+                    return false;
+                }
+                old.retire(deadline);
+                old.expires_at.is_some_and(|exp| exp > now)
+            });
+            entries.extend(configured);
+        });
+    }
+}
+
+#[cfg(feature = "config")]
+fn config_entries(config: &BaseConfig) -> Result<Vec<TokenEntry>, TokenHashError> {
+    let principal = Principal::new(DEFAULT_PRINCIPAL).with_scopes([ALL_SCOPES]);
+    let mut entries = Vec::new();
+    if let Some(token) = &config.auth_token {
+        entries.push(TokenEntry::new(token.expose(), principal.clone()).origin(Origin::Config));
+    }
+    if let Some(hash) = &config.auth_token_hash {
+        entries.push(TokenEntry::hashed(hash, principal)?.origin(Origin::Config));
+    }
+    Ok(entries)
+}
+
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Handle of the thread started by [`TokenStore::watch_file`].
+///
+/// Watching stops when it is dropped.
+pub struct TokenFileWatcher {
+    _stop: mpsc::Sender<()>,
+}
+
+/// Error returned when reading a token file fails.
+#[derive(Debug)]
+pub enum TokenFileError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The line (1-based) is not a valid entry.
+    Invalid {
+        /// Line number.
+        line: usize,
+        /// What is wrong with it.
+        reason: String,
+    },
+}
+
+impl fmt::Display for TokenFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read token file: {e}"),
+            Self::Invalid { line, reason } => write!(f, "invalid token file line {line}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for TokenFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Invalid { .. } => None,
+        }
+    }
+}
+
+fn parse_file(path: &Path) -> Result<Vec<TokenEntry>, TokenFileError> {
+    let contents = fs::read_to_string(path).map_err(TokenFileError::Io)?;
+    parse_entries(&contents)
+}
+
+fn parse_entries(contents: &str) -> Result<Vec<TokenEntry>, TokenFileError> {
+    let mut entries = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |reason: String| TokenFileError::Invalid {
+            line: index + 1,
+            reason,
+        };
+
+        let mut fields = line.split_whitespace();
+        let token = fields.next().unwrap_or_default();
+        let name = fields
+            .next()
+            .ok_or_else(|| invalid("missing principal name".to_string()))?;
+        let mut principal = Principal::new(name);
+        let mut not_before = None;
+        let mut expires_at = None;
+
+        for attribute in fields {
+            let (key, value) = attribute
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected key=value, got `{attribute}`")))?;
+            let timestamp = || {
+                parse_rfc3339(value).ok_or_else(|| invalid(format!("invalid timestamp `{value}`")))
+            };
+            match key {
+                "scopes" => principal = principal.with_scopes(value.split(',')),
+                "not_before" => not_before = Some(timestamp()?),
+                "expires" => expires_at = Some(timestamp()?),
+                _ => return Err(invalid(format!("unknown attribute `{key}`"))),
+            }
+        }
+
+        let secret = TokenSecret::parse(token).map_err(|e| invalid(e.to_string()))?;
+        entries.push(TokenEntry {
+            secret,
+            principal,
+            not_before,
+            expires_at,
+            origin: Origin::File,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "
+        # comment
+        old-token  ci     scopes=read expires=2000-01-01T00:00:00Z
+        new-token  ci     scopes=read,tool:*
+        next-token admin  scopes=* not_before=2999-01-01T00:00:00Z
+    ";
+
+    #[test]
+    fn parses_token_file() {
+        let store = TokenStore::new(parse_entries(FILE).unwrap());
+        assert!(store.authenticate("old-token").is_none());
+        assert!(store.authenticate("next-token").is_none());
+        let principal = store.authenticate("new-token").unwrap();
+        assert_eq!(principal.name(), "ci");
+        assert!(principal.has_scope("tool:search"));
+        assert!(store.authenticate("unknown").is_none());
+    }
+
+    #[test]
+    fn accepts_hashed_tokens() {
+        let hash = super::super::hash_token("hashed-token", "pepper");
+        let contents = format!("{hash} ci scopes=read\n");
+        let store = TokenStore::new(parse_entries(&contents).unwrap()).with_pepper("pepper");
+        assert_eq!(store.authenticate("hashed-token").unwrap().name(), "ci");
+        assert!(store.authenticate(&hash).is_none());
+
+        let store = TokenStore::new([TokenEntry::hashed(&hash, Principal::new("ci")).unwrap()]);
+        assert!(store.authenticate("hashed-token").is_none());
+        assert!(TokenEntry::hashed("plaintext", Principal::new("ci")).is_err());
+    }
+
+    #[test]
+    fn reports_invalid_lines() {
+        for (contents, line) in [
+            ("token\n", 1),
+            ("a b\n\nc d when=now\n", 3),
+            ("a b expires=tomorrow\n", 1),
+            ("sha256$00 b\n", 1),
+        ] {
+            assert!(matches!(
+                parse_entries(contents),
+                Err(TokenFileError::Invalid { line: l, .. }) if l == line
+            ));
+        }
+    }
+
+    #[test]
+    fn rotation_keeps_old_token_during_grace() {
+        let store = TokenStore::new([TokenEntry::new("old", Principal::new("ci"))]);
+        store.rotate("new", Principal::new("ci"), Duration::from_secs(60));
+        assert!(store.authenticate("old").is_some());
+        assert!(store.authenticate("new").is_some());
+
+        store.rotate("newer", Principal::new("ci"), Duration::ZERO);
+        assert!(store.authenticate("old").is_none());
+        assert!(store.authenticate("new").is_none());
+        assert!(store.authenticate("newer").is_some());
+    }
+
+    #[test]
+    fn reload_keeps_tokens_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens");
+        fs::write(&path, "first a\n").unwrap();
+        let store = TokenStore::from_file(&path).unwrap();
+
+        fs::write(&path, "broken\n").unwrap();
+        assert!(store.reload(&path).is_err());
+        assert!(store.authenticate("first").is_some());
+
+        fs::write(&path, "second a\n").unwrap();
+        store.reload(&path).unwrap();
+        assert!(store.authenticate("first").is_none());
+        assert!(store.authenticate("second").is_some());
+    }
+
+    #[test]
+    fn watcher_picks_up_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens");
+        fs::write(&path, "first a\n").unwrap();
+        let store = TokenStore::default();
+        let _watcher = store.watch_file(&path, Duration::from_millis(10)).unwrap();
+        assert!(store.authenticate("first").is_some());
+
+        fs::write(&path, "second-token a\n").unwrap();
+        for _ in 0..200 {
+            if store.authenticate("second-token").is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(store.authenticate("second-token").is_some());
+        assert!(store.authenticate("first").is_none());
+    }
+
+    #[cfg(feature = "reload")]
+    #[tokio::test]
+    async fn follows_config_changes() {
+        let mut config = BaseConfig::from_env();
+        config.auth_token = Some(Secret::new("first"));
+        config.auth_token_hash = None;
+        let (tx, rx) = tokio::sync::watch::channel(Arc::new(config.clone()));
+        let store = TokenStore::from_config(&config).unwrap();
+        store.insert(TokenEntry::new("extra", Principal::new("ci")));
+        let task = store.follow_config(rx, Duration::from_secs(60));
+        assert!(store.authenticate("first").unwrap().has_scope("tool:x"));
+
+        config.auth_token = Some(Secret::new("second"));
+        tx.send_replace(Arc::new(config.clone()));
+        drop(tx);
+        task.await.unwrap();
+        // The replaced config token keeps working during the grace period,
+        // and tokens added otherwise are kept.
+        for token in ["first", "second", "extra"] {
+            assert!(store.authenticate(token).is_some(), "{token}");
+        }
+
+        let (tx, rx) = tokio::sync::watch::channel(Arc::new(config.clone()));
+        let task = store.follow_config(rx, Duration::ZERO);
+        config.auth_token = Some(Secret::new("third"));
+        tx.send_replace(Arc::new(config));
+        drop(tx);
+        task.await.unwrap();
+        for (token, valid) in [
+            ("first", false),
+            ("second", false),
+            ("third", true),
+            ("extra", true),
+        ] {
+            assert_eq!(store.authenticate(token).is_some(), valid, "{token}");
+        }
+    }
+}
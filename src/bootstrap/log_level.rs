@@ -0,0 +1,133 @@
+//! Runtime-reloadable log filter.
+
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use tracing_subscriber::{filter::ParseError, reload, EnvFilter, Registry};
+
+/// Handle of the installed reloadable filter, set by the `init_tracing*` functions.
+static HANDLE: OnceLock<LogLevelHandle> = OnceLock::new();
+
+/// Error returned when the log filter cannot be changed.
+#[derive(Debug)]
+pub enum LogLevelError {
+    /// The directives are not valid `EnvFilter` syntax.
+    InvalidFilter(ParseError),
+    /// The subscriber owning the filter has been dropped.
+    Reload(reload::Error),
+}
+
+impl fmt::Display for LogLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFilter(e) => write!(f, "invalid log filter: {e}"),
+            Self::Reload(e) => write!(f, "failed to reload log filter: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LogLevelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidFilter(e) => Some(e),
+            Self::Reload(e) => Some(e),
+        }
+    }
+}
+
+/// Handle for inspecting and replacing the log filter at runtime.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use mcp_core::bootstrap::log_level_handle;
+///
+/// if let Some(handle) = log_level_handle() {
+///     handle.set("my_server=trace,info").unwrap();
+///     assert_eq!(handle.current(), "my_server=trace,info");
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LogLevelHandle {
+    inner: reload::Handle<EnvFilter, Registry>,
+    startup: Arc<str>,
+}
+
+impl LogLevelHandle {
+    /// Wrap `filter` in a reloadable layer and return it with its handle.
+    pub(crate) fn new(filter: EnvFilter) -> (reload::Layer<EnvFilter, Registry>, Self) {
+        let startup = filter.to_string().into();
+        let (layer, inner) = reload::Layer::new(filter);
+        (layer, Self { inner, startup })
+    }
+
+    /// Make this the handle returned by [`log_level_handle`].
+    pub(crate) fn register(&self) {
+        let _ = HANDLE.set(self.clone());
+    }
+
+    /// The currently active filter directives.
+    pub fn current(&self) -> String {
+        self.inner
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Replace the filter with the given directives (`EnvFilter` syntax).
+    pub fn set(&self, directives: &str) -> Result<(), LogLevelError> {
+        let filter = EnvFilter::try_new(directives).map_err(LogLevelError::InvalidFilter)?;
+        self.inner.reload(filter).map_err(LogLevelError::Reload)?;
+        tracing::info!(filter = directives, "log filter changed");
+        Ok(())
+    }
+
+    /// Restore the filter tracing was initialized with.
+    pub fn reset(&self) -> Result<(), LogLevelError> {
+        self.set(&self.startup)
+    }
+}
+
+/// The handle of the filter installed by [`init_tracing`](super::init_tracing)
+/// or [`init_tracing_with`](super::init_tracing_with).
+///
+/// Returns `None` if tracing was not initialized by this crate.
+pub fn log_level_handle() -> Option<LogLevelHandle> {
+    HANDLE.get().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn reloads_filter() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+
+        assert_eq!(handle.current(), "info");
+        handle.set("my_server=debug,warn").unwrap();
+        assert_eq!(handle.current(), "my_server=debug,warn");
+        handle.reset().unwrap();
+        assert_eq!(handle.current(), "info");
+    }
+
+    #[test]
+    fn rejects_invalid_filter() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let _subscriber = tracing_subscriber::registry().with(layer);
+
+        let result = handle.set("my_server=loud");
+        assert!(matches!(result, Err(LogLevelError::InvalidFilter(_))));
+        assert_eq!(handle.current(), "info");
+    }
+
+    #[test]
+    fn reports_dropped_subscriber() {
+        let (layer, handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        drop(layer);
+
+        let result = handle.set("debug");
+        assert!(matches!(result, Err(LogLevelError::Reload(_))));
+    }
+}
@@ -1,5 +1,19 @@
 //! Bootstrap utilities for MCP servers.
 
+mod log_level;
+#[cfg(feature = "otel")]
+pub mod otel;
+mod rolling;
+pub mod testing;
 mod tracing_init;
 
-pub use tracing_init::init_tracing;
+pub use log_level::{log_level_handle, LogLevelError, LogLevelHandle};
+#[cfg(feature = "otel")]
+pub use otel::{OtelError, OtelGuard};
+pub use rolling::{RollingFileOptions, RollingFileWriter};
+#[cfg(feature = "otel")]
+pub use tracing_init::init_tracing_otlp;
+pub use tracing_init::{
+    init_tracing, init_tracing_with, try_init_tracing, LogDestination, LogFormat, TracingError,
+    TracingGuard, TracingOptions,
+};
@@ -0,0 +1,188 @@
+//! OpenTelemetry export of spans and logs via OTLP.
+//!
+//! The exporters are configured through the standard `OTEL_*` environment
+//! variables, e.g. `OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_EXPORTER_OTLP_HEADERS`,
+//! `OTEL_SERVICE_NAME` and `OTEL_RESOURCE_ATTRIBUTES`. Spans and logs are sent
+//! as protobuf over HTTP.
+
+use std::fmt;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_otlp::{ExporterBuildError, LogExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    logs::SdkLoggerProvider, propagation::TraceContextPropagator, trace::SdkTracerProvider,
+    Resource,
+};
+use tracing::Subscriber;
+use tracing_subscriber::{filter::filter_fn, registry::LookupSpan, Layer};
+
+/// Instrumentation scope name reported for spans and logs.
+const SCOPE_NAME: &str = "mcp-core";
+
+/// Error returned when the OTLP exporters cannot be set up.
+#[derive(Debug)]
+pub struct OtelError(ExporterBuildError);
+
+impl fmt::Display for OtelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to build OTLP exporter: {}", self.0)
+    }
+}
+
+impl std::error::Error for OtelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Keeps the OpenTelemetry providers alive.
+///
+/// Dropping the guard flushes pending spans and logs and shuts the exporters
+/// down, so hold on to it for the lifetime of the process. Shutdown errors
+/// are logged as warnings.
+#[must_use = "dropping the guard shuts down OpenTelemetry export"]
+pub struct OtelGuard {
+    tracer_provider: SdkTracerProvider,
+    logger_provider: SdkLoggerProvider,
+}
+
+impl OtelGuard {
+    /// Build the OTLP exporters from the `OTEL_*` environment variables.
+    pub(crate) fn from_env() -> Result<Self, OtelError> {
+        Self::build(None)
+    }
+
+    /// Build the exporters, optionally overriding the collector base URL.
+    ///
+    /// The override takes the same form as `OTEL_EXPORTER_OTLP_ENDPOINT`,
+    /// i.e. `/v1/traces` and `/v1/logs` are appended per signal.
+    fn build(endpoint: Option<&str>) -> Result<Self, OtelError> {
+        let mut spans = SpanExporter::builder().with_http();
+        let mut logs = LogExporter::builder().with_http();
+        if let Some(endpoint) = endpoint {
+            let endpoint = endpoint.trim_end_matches('/');
+            spans = spans.with_endpoint(format!("{endpoint}/v1/traces"));
+            logs = logs.with_endpoint(format!("{endpoint}/v1/logs"));
+        }
+
+        let resource = Resource::builder().build();
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(spans.build().map_err(OtelError)?)
+            .with_resource(resource.clone())
+            .build();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_batch_exporter(logs.build().map_err(OtelError)?)
+            .with_resource(resource)
+            .build();
+
+        Ok(Self {
+            tracer_provider,
+            logger_provider,
+        })
+    }
+
+    /// Register the providers globally and install the W3C trace-context
+    /// propagator used for `traceparent` headers.
+    pub(crate) fn install_globals(&self) {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        opentelemetry::global::set_tracer_provider(self.tracer_provider.clone());
+    }
+
+    /// Tracing layers exporting spans and log events to the providers.
+    pub(crate) fn layers<S>(&self) -> impl Layer<S> + Send + Sync
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let spans =
+            tracing_opentelemetry::layer().with_tracer(self.tracer_provider.tracer(SCOPE_NAME));
+        // The exporter's HTTP client logs through `tracing` as well; feeding
+        // those events back into the exporter would loop.
+        let logs = OpenTelemetryTracingBridge::new(&self.logger_provider)
+            .with_filter(filter_fn(|meta| !is_exporter_target(meta.target())));
+        spans.and_then(logs)
+    }
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        // Reported to the remaining layers; the logger provider drops events
+        // once it is shut down.
+        if let Err(e) = self.tracer_provider.shutdown() {
+            tracing::warn!(error = %e, "failed to shut down OpenTelemetry tracer provider");
+        }
+        if let Err(e) = self.logger_provider.shutdown() {
+            tracing::warn!(error = %e, "failed to shut down OpenTelemetry logger provider");
+        }
+    }
+}
+
+fn is_exporter_target(target: &str) -> bool {
+    ["opentelemetry", "hyper", "reqwest", "h2", "tower"]
+        .iter()
+        .any(|prefix| target.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Bytes, extract::State, http::Uri, routing::post, Router};
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::prelude::*;
+
+    type Received = Arc<Mutex<Vec<(String, usize)>>>;
+
+    /// Minimal OTLP/HTTP collector recording the path and size of each export.
+    async fn spawn_collector() -> (String, Received) {
+        async fn collect(State(received): State<Received>, uri: Uri, body: Bytes) {
+            received
+                .lock()
+                .unwrap()
+                .push((uri.path().to_string(), body.len()));
+        }
+
+        let received = Received::default();
+        let app = Router::new()
+            .route("/v1/traces", post(collect))
+            .route("/v1/logs", post(collect))
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}"), received)
+    }
+
+    #[test]
+    fn exporter_target_filter() {
+        assert!(is_exporter_target("hyper_util::client"));
+        assert!(is_exporter_target("opentelemetry_sdk"));
+        assert!(!is_exporter_target("my_server::tools"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn exports_spans_and_logs_to_collector() {
+        let (endpoint, received) = spawn_collector().await;
+
+        tokio::task::spawn_blocking(move || {
+            let guard = OtelGuard::build(Some(&endpoint)).unwrap();
+            let subscriber = tracing_subscriber::registry().with(guard.layers());
+            tracing::subscriber::with_default(subscriber, || {
+                let span = tracing::info_span!("mcp_request", method = "tools/call");
+                let _entered = span.enter();
+                tracing::info!("handled request");
+            });
+            // Dropping the guard flushes both batch processors.
+            drop(guard);
+        })
+        .await
+        .unwrap();
+
+        let received = received.lock().unwrap();
+        assert!(received
+            .iter()
+            .any(|(path, len)| path == "/v1/traces" && *len > 0));
+        assert!(received
+            .iter()
+            .any(|(path, len)| path == "/v1/logs" && *len > 0));
+    }
+}
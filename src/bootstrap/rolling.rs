@@ -0,0 +1,267 @@
+//! Log file writer with size and time based rotation.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Options for writing logs to rolling files.
+///
+/// The active file is `<directory>/<file_name>`. When it grows beyond
+/// [`max_bytes`](Self::max_bytes) or has been open longer than
+/// [`max_age`](Self::max_age), it is renamed to `<file_name>.<unix-millis>` and
+/// a fresh file is started. At most [`max_files`](Self::max_files) rotated
+/// files are kept; older ones are deleted.
+#[derive(Clone, Debug)]
+pub struct RollingFileOptions {
+    /// Directory holding the log files (created if missing).
+    pub directory: PathBuf,
+    /// Name of the active log file (default: `server.log`).
+    pub file_name: String,
+    /// Rotate once the active file reaches this size.
+    pub max_bytes: Option<u64>,
+    /// Rotate once the active file has been written to for this long.
+    pub max_age: Option<Duration>,
+    /// Number of rotated files to keep (`None` keeps all).
+    pub max_files: Option<usize>,
+}
+
+impl RollingFileOptions {
+    /// Daily rotation with 10 MiB size cap and 7 retained files in `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            file_name: "server.log".to_string(),
+            max_bytes: Some(10 * 1024 * 1024),
+            max_age: Some(Duration::from_secs(24 * 60 * 60)),
+            max_files: Some(7),
+        }
+    }
+
+    /// Default rolling options writing to `<data_path>/logs`.
+    pub fn in_data_path(data_path: &Path) -> Self {
+        Self::new(data_path.join("logs"))
+    }
+}
+
+struct ActiveFile {
+    file: File,
+    size: u64,
+    opened_at: SystemTime,
+}
+
+/// [`MakeWriter`] appending to a log file that rotates by size and age.
+pub struct RollingFileWriter {
+    options: RollingFileOptions,
+    active: Mutex<ActiveFile>,
+}
+
+impl RollingFileWriter {
+    /// Open (or create) the active log file.
+    pub fn new(options: RollingFileOptions) -> io::Result<Self> {
+        fs::create_dir_all(&options.directory)?;
+        let active = open_active(&options.directory.join(&options.file_name))?;
+        Ok(Self {
+            options,
+            active: Mutex::new(active),
+        })
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.options.directory.join(&self.options.file_name)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ActiveFile> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn needs_rotation(&self, active: &ActiveFile, incoming: usize) -> bool {
+        if active.size == 0 {
+            return false;
+        }
+        let too_large = self
+            .options
+            .max_bytes
+            .is_some_and(|max| active.size + incoming as u64 > max);
+        let too_old = self.options.max_age.is_some_and(|max| {
+            active
+                .opened_at
+                .elapsed()
+                .map(|age| age >= max)
+                .unwrap_or(false)
+        });
+        too_large || too_old
+    }
+
+    fn rotate(&self, active: &mut ActiveFile) -> io::Result<()> {
+        active.file.flush()?;
+        let path = self.active_path();
+        let mut millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut rotated = self.rotated_path(millis);
+        while rotated.exists() {
+            millis += 1;
+            rotated = self.rotated_path(millis);
+        }
+        fs::rename(&path, &rotated)?;
+        *active = open_active(&path)?;
+        self.prune()
+    }
+
+    fn rotated_path(&self, millis: u128) -> PathBuf {
+        self.options
+            .directory
+            .join(format!("{}.{millis}", self.options.file_name))
+    }
+
+    /// Delete the oldest rotated files beyond `max_files`.
+    fn prune(&self) -> io::Result<()> {
+        let Some(max_files) = self.options.max_files else {
+            return Ok(());
+        };
+        let prefix = format!("{}.", self.options.file_name);
+        let mut rotated: Vec<(u128, PathBuf)> = fs::read_dir(&self.options.directory)?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().into_string().ok()?;
+                let stamp = name.strip_prefix(&prefix)?.parse().ok()?;
+                Some((stamp, entry.path()))
+            })
+            .collect();
+        if rotated.len() <= max_files {
+            return Ok(());
+        }
+        rotated.sort_unstable_by_key(|(stamp, _)| *stamp);
+        for (_, path) in &rotated[..rotated.len() - max_files] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn open_active(path: &Path) -> io::Result<ActiveFile> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok(ActiveFile {
+        file,
+        size,
+        opened_at: SystemTime::now(),
+    })
+}
+
+/// Writer handed out per event by [`RollingFileWriter`].
+pub struct RollingWriter<'a>(&'a RollingFileWriter);
+
+impl Write for RollingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut active = self.0.lock();
+        if self.0.needs_rotation(&active, buf.len()) {
+            self.0.rotate(&mut active)?;
+        }
+        let written = active.file.write(buf)?;
+        active.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().file.flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFileWriter {
+    type Writer = RollingWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RollingWriter(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(dir: &Path) -> RollingFileOptions {
+        RollingFileOptions {
+            directory: dir.join("logs"),
+            file_name: "test.log".to_string(),
+            max_bytes: None,
+            max_age: None,
+            max_files: None,
+        }
+    }
+
+    fn rotated_count(dir: &Path) -> usize {
+        fs::read_dir(dir.join("logs"))
+            .unwrap()
+            .filter(|e| {
+                let name = e.as_ref().unwrap().file_name();
+                name.to_str().unwrap().starts_with("test.log.")
+            })
+            .count()
+    }
+
+    #[test]
+    fn creates_directory_and_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RollingFileWriter::new(options(dir.path())).unwrap();
+        writer.make_writer().write_all(b"one\n").unwrap();
+        writer.make_writer().write_all(b"two\n").unwrap();
+
+        let contents = fs::read_to_string(dir.path().join("logs/test.log")).unwrap();
+        assert_eq!(contents, "one\ntwo\n");
+        assert_eq!(rotated_count(dir.path()), 0);
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RollingFileWriter::new(RollingFileOptions {
+            max_bytes: Some(8),
+            ..options(dir.path())
+        })
+        .unwrap();
+        writer.make_writer().write_all(b"first\n").unwrap();
+        writer.make_writer().write_all(b"second\n").unwrap();
+
+        let contents = fs::read_to_string(dir.path().join("logs/test.log")).unwrap();
+        assert_eq!(contents, "second\n");
+        assert_eq!(rotated_count(dir.path()), 1);
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RollingFileWriter::new(RollingFileOptions {
+            max_age: Some(Duration::ZERO),
+            ..options(dir.path())
+        })
+        .unwrap();
+        writer.make_writer().write_all(b"first\n").unwrap();
+        writer.make_writer().write_all(b"second\n").unwrap();
+
+        assert_eq!(rotated_count(dir.path()), 1);
+    }
+
+    #[test]
+    fn prunes_old_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RollingFileWriter::new(RollingFileOptions {
+            max_bytes: Some(1),
+            max_files: Some(2),
+            ..options(dir.path())
+        })
+        .unwrap();
+        for line in ["a\n", "b\n", "c\n", "d\n", "e\n"] {
+            writer.make_writer().write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(rotated_count(dir.path()), 2);
+        let contents = fs::read_to_string(dir.path().join("logs/test.log")).unwrap();
+        assert_eq!(contents, "e\n");
+    }
+}
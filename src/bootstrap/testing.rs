@@ -0,0 +1,150 @@
+//! Helpers for asserting on log output in tests.
+
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::{fmt, fmt::MakeWriter, prelude::*, EnvFilter};
+
+/// Shared in-memory buffer usable as a `tracing_subscriber` writer.
+#[derive(Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Everything written so far, lossily decoded as UTF-8.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.lock()).into_owned()
+    }
+
+    /// Discard everything written so far.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogBuffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Log events captured by [`capture_logs`].
+///
+/// Capturing stops when this value is dropped.
+pub struct CapturedLogs {
+    buffer: LogBuffer,
+    _guard: DefaultGuard,
+}
+
+impl CapturedLogs {
+    /// All captured output, one formatted event per line.
+    pub fn contents(&self) -> String {
+        self.buffer.contents()
+    }
+
+    /// The captured events, one entry per line.
+    pub fn lines(&self) -> Vec<String> {
+        self.contents().lines().map(str::to_string).collect()
+    }
+
+    /// Whether any captured event contains `needle`.
+    pub fn contains(&self, needle: &str) -> bool {
+        self.contents().contains(needle)
+    }
+
+    /// Discard the events captured so far.
+    pub fn clear(&self) {
+        self.buffer.clear();
+    }
+}
+
+/// Capture log events of the current thread into a buffer.
+///
+/// Installs a thread-local default subscriber with the given filter (in
+/// `EnvFilter` syntax, `RUST_LOG` is ignored) that formats events as plain
+/// text without timestamps or colors. The global subscriber is left alone, so
+/// this works regardless of whether tracing has been initialized.
+///
+/// Events from other threads are not captured; in async tests use the
+/// current-thread runtime (the `#[tokio::test]` default).
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_core::bootstrap::testing::capture_logs;
+///
+/// let logs = capture_logs("debug");
+/// tracing::info!(tool = "search", "tool called");
+/// assert!(logs.contains("tool called"));
+/// assert!(logs.contains("tool=\"search\""));
+/// ```
+pub fn capture_logs(filter: &str) -> CapturedLogs {
+    let buffer = LogBuffer::default();
+    let subscriber = tracing_subscriber::registry()
+        .with(EnvFilter::new(filter))
+        .with(
+            fmt::layer()
+                .with_writer(buffer.clone())
+                .with_ansi(false)
+                .without_time(),
+        );
+
+    CapturedLogs {
+        buffer,
+        _guard: tracing::subscriber::set_default(subscriber),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_events_matching_filter() {
+        let logs = capture_logs("info");
+        tracing::info!("visible");
+        tracing::debug!("hidden");
+
+        assert!(logs.contains("visible"));
+        assert!(!logs.contains("hidden"));
+        assert_eq!(logs.lines().len(), 1);
+    }
+
+    #[test]
+    fn stops_capturing_when_dropped() {
+        let logs = capture_logs("info");
+        let buffer = logs.buffer.clone();
+        drop(logs);
+
+        tracing::info!("after drop");
+        assert!(buffer.contents().is_empty());
+    }
+
+    #[test]
+    fn clear_discards_captured_events() {
+        let logs = capture_logs("info");
+        tracing::info!("first");
+        logs.clear();
+        tracing::info!("second");
+
+        assert!(!logs.contains("first"));
+        assert!(logs.contains("second"));
+    }
+}
@@ -1,6 +1,22 @@
 //! Tracing initialization utilities.
 
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use std::fmt as std_fmt;
+use std::io;
+use std::sync::Mutex;
+
+use tracing_subscriber::{
+    fmt, fmt::MakeWriter, layer::Layered, prelude::*, reload, EnvFilter, Layer, Registry,
+};
+
+use super::log_level::{log_level_handle, LogLevelHandle};
+#[cfg(feature = "otel")]
+use super::otel::{OtelError, OtelGuard};
+use super::rolling::{RollingFileOptions, RollingFileWriter};
+
+/// Subscriber the output layers are stacked on: the registry behind the
+/// reloadable filter.
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type BoxedLayer = Box<dyn Layer<Filtered> + Send + Sync>;
 
 /// Initialize tracing with the given default filter.
 ///
@@ -22,19 +38,289 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 /// - `my_crate=debug` - Enable debug level for `my_crate`
 /// - `my_crate=debug,info` - Debug for `my_crate`, info for everything else
 /// - `my_crate::module=trace` - Trace level for a specific module
+///
+/// See [`init_tracing_with`] for JSON output and log files. The filter can be
+/// changed at runtime through [`log_level_handle`](super::log_level_handle).
+///
+/// Repeated calls are no-ops. If a global subscriber was already installed by
+/// other code, it is left in place and the failure is logged through it; use
+/// [`try_init_tracing`] to handle that case.
 pub fn init_tracing(default_filter: &str) {
+    if let Err(e) = try_init_tracing(default_filter) {
+        tracing::warn!(error = %e, "tracing not initialized");
+    }
+}
+
+/// Fallible variant of [`init_tracing`].
+///
+/// Returns `Ok(())` if tracing is already initialized by this crate, and
+/// [`TracingError::AlreadyInitialized`] if another global subscriber is set.
+pub fn try_init_tracing(default_filter: &str) -> Result<(), TracingError> {
+    match init_tracing_with(TracingOptions::new(default_filter)) {
+        Ok(_guard) => Ok(()),
+        Err(TracingError::AlreadyInitialized) if log_level_handle().is_some() => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Initialize tracing with OpenTelemetry export in addition to stderr output.
+///
+/// Spans and log events are exported via OTLP/HTTP to the collector configured
+/// through the standard `OTEL_*` environment variables (see
+/// [`otel`](super::otel)). The W3C trace-context propagator is installed so
+/// that incoming `traceparent` headers continue the caller's trace.
+///
+/// The returned guard flushes and shuts down the exporters when dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use mcp_core::init_tracing_otlp;
+///
+/// let _otel = init_tracing_otlp("my_server=debug,info").expect("OTLP exporter");
+/// ```
+#[cfg(feature = "otel")]
+pub fn init_tracing_otlp(default_filter: &str) -> Result<TracingGuard, TracingError> {
+    init_tracing_with(TracingOptions {
+        otlp: true,
+        ..TracingOptions::new(default_filter)
+    })
+}
+
+/// Output format of log events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable text, one line per event.
+    #[default]
+    Text,
+    /// Newline-delimited JSON with stable field names: `timestamp`, `level`,
+    /// `target`, `message`, the event's own fields at the top level and the
+    /// current span under `span`.
+    Json,
+}
+
+/// Where log events are written to.
+#[derive(Clone, Debug, Default)]
+pub enum LogDestination {
+    /// Standard error.
+    #[default]
+    Stderr,
+    /// Rolling files, see [`RollingFileOptions`].
+    File(RollingFileOptions),
+}
+
+/// Options for [`init_tracing_with`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::path::Path;
+/// use mcp_core::bootstrap::{init_tracing_with, LogDestination, LogFormat, RollingFileOptions, TracingOptions};
+///
+/// let _guard = init_tracing_with(TracingOptions {
+///     format: LogFormat::Json,
+///     destination: LogDestination::File(RollingFileOptions::in_data_path(Path::new("./data"))),
+///     ..TracingOptions::new("my_server=debug,info")
+/// })
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct TracingOptions {
+    /// Filter used when `RUST_LOG` is not set.
+    pub default_filter: String,
+    /// Output format (default: text).
+    pub format: LogFormat,
+    /// Output destination (default: stderr).
+    pub destination: LogDestination,
+    /// Also export spans and logs via OTLP (default: false).
+    #[cfg(feature = "otel")]
+    pub otlp: bool,
+}
+
+impl TracingOptions {
+    /// Text output to stderr with the given default filter.
+    pub fn new(default_filter: &str) -> Self {
+        Self {
+            default_filter: default_filter.to_string(),
+            format: LogFormat::default(),
+            destination: LogDestination::default(),
+            #[cfg(feature = "otel")]
+            otlp: false,
+        }
+    }
+}
+
+/// Error returned when tracing cannot be initialized.
+#[derive(Debug)]
+pub enum TracingError {
+    /// The log file could not be opened.
+    Io(io::Error),
+    /// The OTLP exporters could not be built.
+    #[cfg(feature = "otel")]
+    Otel(OtelError),
+    /// A global subscriber was already installed, by an earlier call or by
+    /// other code.
+    AlreadyInitialized,
+}
+
+impl std_fmt::Display for TracingError {
+    fn fmt(&self, f: &mut std_fmt::Formatter<'_>) -> std_fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to open log file: {e}"),
+            #[cfg(feature = "otel")]
+            Self::Otel(e) => e.fmt(f),
+            Self::AlreadyInitialized => write!(f, "tracing already initialized"),
+        }
+    }
+}
+
+impl std::error::Error for TracingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            #[cfg(feature = "otel")]
+            Self::Otel(e) => Some(e),
+            Self::AlreadyInitialized => None,
+        }
+    }
+}
+
+/// Keeps resources needed by the tracing subscriber alive.
+///
+/// Hold on to it for the lifetime of the process; with OTLP export enabled,
+/// dropping it flushes and shuts down the exporters.
+#[must_use = "dropping the guard may stop log export"]
+pub struct TracingGuard {
+    #[cfg(feature = "otel")]
+    _otel: Option<OtelGuard>,
+}
+
+/// Serializes initialization so that concurrent first calls install only once.
+static INIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Initialize tracing with explicit format, destination and export options.
+///
+/// The other initializers are shorthands for this function. Like
+/// [`init_tracing`], the filter can be overridden by `RUST_LOG`. Unlike it,
+/// [`TracingError::AlreadyInitialized`] is returned if a global subscriber is
+/// already installed, including by an earlier call, since the new options
+/// would not take effect.
+pub fn init_tracing_with(options: TracingOptions) -> Result<TracingGuard, TracingError> {
+    let _lock = INIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if log_level_handle().is_some() {
+        return Err(TracingError::AlreadyInitialized);
+    }
+
+    #[cfg_attr(not(feature = "otel"), allow(unused_mut))]
+    let mut layers = vec![match options.destination {
+        LogDestination::Stderr => fmt_layer(options.format, std::io::stderr, true),
+        LogDestination::File(rolling) => {
+            let writer = RollingFileWriter::new(rolling).map_err(TracingError::Io)?;
+            fmt_layer(options.format, writer, false)
+        }
+    }];
+
+    #[cfg(feature = "otel")]
+    let otel = if options.otlp {
+        let guard = OtelGuard::from_env().map_err(TracingError::Otel)?;
+        layers.push(guard.layers().boxed());
+        Some(guard)
+    } else {
+        None
+    };
+
+    install(&options.default_filter, layers)?;
+
+    #[cfg(feature = "otel")]
+    if let Some(guard) = &otel {
+        guard.install_globals();
+    }
+
+    Ok(TracingGuard {
+        #[cfg(feature = "otel")]
+        _otel: otel,
+    })
+}
+
+/// Install the global subscriber: the reloadable filter followed by `layers`.
+fn install(default_filter: &str, layers: Vec<BoxedLayer>) -> Result<(), TracingError> {
     let filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let (filter, handle) = LogLevelHandle::new(filter);
 
     tracing_subscriber::registry()
-        .with(fmt::layer().with_writer(std::io::stderr))
         .with(filter)
-        .init();
+        .with(layers)
+        .try_init()
+        .map_err(|_| TracingError::AlreadyInitialized)?;
+
+    handle.register();
+    Ok(())
+}
+
+fn fmt_layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    // Note: tracing can only be initialized once per process,
-    // so we can't really unit test init_tracing without affecting other tests.
-    // Integration tests should verify this works correctly.
+    use super::*;
+    use crate::bootstrap::testing::LogBuffer;
+
+    // The layers are tested with a scoped default subscriber; the global
+    // initializers are only exercised by `repeated_initialization_is_a_no_op`
+    // because the global subscriber can be set once per process.
+    #[test]
+    fn json_format_uses_stable_field_names() {
+        let buffer = LogBuffer::default();
+        let layer = fmt_layer(LogFormat::Json, buffer.clone(), false);
+        let (filter, _handle) = LogLevelHandle::new(EnvFilter::new("info"));
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(vec![layer]);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("mcp_session", session_id = "abc");
+            let _entered = span.enter();
+            tracing::info!(tool = "search", "tool called");
+        });
+
+        let output = buffer.contents();
+        let event: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["message"], "tool called");
+        assert_eq!(event["tool"], "search");
+        assert_eq!(event["span"]["name"], "mcp_session");
+        assert_eq!(event["span"]["session_id"], "abc");
+        assert!(event["timestamp"].is_string());
+        assert!(event["target"].is_string());
+    }
+
+    #[test]
+    fn repeated_initialization_is_a_no_op() {
+        try_init_tracing("off").unwrap();
+        try_init_tracing("debug").unwrap();
+        init_tracing("trace");
+        assert!(matches!(
+            init_tracing_with(TracingOptions::new("trace")),
+            Err(TracingError::AlreadyInitialized)
+        ));
+
+        let handle = log_level_handle().unwrap();
+        if std::env::var_os("RUST_LOG").is_none() {
+            assert_eq!(handle.current(), "off");
+        }
+    }
 }
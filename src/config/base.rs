@@ -1,8 +1,18 @@
 //! Base configuration for MCP and web servers.
 
-use super::safe_path::{safe_resolve, SafePathError};
+#[cfg(unix)]
+use super::data_dir::{DataDir, DataDirError};
+use super::data_roots::{Access, DataRoots, DataRootsParseError};
+use super::env_file::{env_var, read_env_file, ConfigFileError, Lookup};
+use super::rate_limit::RateLimits;
+#[cfg(unix)]
+use super::safe_dir::SafeDir;
+use super::safe_path::{safe_resolve, safe_resolve_for_create, SafePathError};
+use super::secret::{lookup_secret, NoSecrets, Secret, SecretError, SecretProvider};
 use super::token::generate_random_token;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// Base configuration shared by MCP and web servers.
 ///
@@ -13,21 +23,36 @@ use std::path::PathBuf;
 /// | `HOST` | `127.0.0.1` | Server bind address |
 /// | `PORT` | `3000` | Server port |
 /// | `DATA_PATH` | `./data` | Base path for data files |
+/// | `DATA_ROOTS` | (none) | Named data directories, see [`DataRoots`] |
 /// | `AUTH_TOKEN` | (none) | Optional auth token |
+/// | `AUTH_TOKEN_HASH` | (none) | Hash of the auth token, instead of `AUTH_TOKEN` |
+/// | `AUTH_TOKEN_PEPPER` | (none) | Secret mixed into token hashes |
+/// | `RUST_LOG` | (none) | Log filter, see `init_tracing` |
+/// | `RATE_LIMIT_*` | (none) | Request rate limits, see [`RateLimits`] |
+///
+/// Secrets (`AUTH_TOKEN` and `AUTH_TOKEN_PEPPER`) are kept out of the process
+/// environment by reading them from the file named by `<NAME>_FILE` (e.g.
+/// Docker and Kubernetes secrets), or from the systemd credential `<NAME>`
+/// in `$CREDENTIALS_DIRECTORY`. These take precedence over the variable
+/// itself; a [`SecretProvider`] passed to [`from_env_with`](Self::from_env_with)
+/// takes precedence over all of them.
 ///
 /// # Example
 ///
 /// ```rust
-/// use mcp_core::BaseConfig;
+/// use mcp_core::{config::AuthToken, BaseConfig};
 ///
 /// let config = BaseConfig::from_env();
-/// let (token, was_generated) = config.get_or_generate_token();
-///
-/// if was_generated {
-///     println!("Generated auth token: {}", token);
+/// match config.resolve_token() {
+///     AuthToken::Configured(token) => { /* accept `token` */ }
+///     AuthToken::Hashed => { /* accept the hash, e.g. with `TokenStore::from_config` */ }
+///     AuthToken::Generated(token) => println!("Generated auth token: {token}"),
 /// }
 /// ```
-#[derive(Clone, Debug)]
+///
+/// `Debug` output and serialization show secrets as `[REDACTED]`; use
+/// [`summary`](Self::summary) for a one-line startup log.
+#[derive(Clone, Debug, Serialize)]
 pub struct BaseConfig {
     /// Server bind address (default: 127.0.0.1)
     pub host: String,
@@ -35,37 +60,113 @@ pub struct BaseConfig {
     pub port: u16,
     /// Base path for data files (default: ./data)
     pub data_path: PathBuf,
+    /// Named data directories (default: none)
+    pub data_roots: DataRoots,
     /// Optional authentication token
-    pub auth_token: Option<String>,
+    pub auth_token: Option<Secret>,
+    /// Hash of the authentication token, e.g. from `mcp_core::auth::hash_token`
+    pub auth_token_hash: Option<String>,
+    /// Pepper the token hashes were created with
+    pub auth_token_pepper: Option<Secret>,
+    /// Log filter directives, in `EnvFilter` syntax
+    pub log_filter: Option<String>,
+    /// Request rate limits (default: unlimited)
+    pub rate_limits: RateLimits,
 }
 
 impl BaseConfig {
     /// Create a new config from environment variables.
+    ///
+    /// A secret file that cannot be read or an invalid `DATA_ROOTS` is logged
+    /// as a warning and skipped, leaving the secret unset and no data roots
+    /// configured. Use [`try_from_env`](Self::try_from_env) or
+    /// [`from_env_with`](Self::from_env_with) to refuse to start instead.
     pub fn from_env() -> Self {
-        Self {
-            host: std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
-            port: std::env::var("PORT")
-                .ok()
-                .and_then(|p| p.parse().ok())
-                .unwrap_or(3000),
-            data_path: std::env::var("DATA_PATH")
+        let skip = |e: ConfigError| {
+            tracing::warn!(error = %e, "ignoring invalid configuration");
+            Ok(())
+        };
+        Self::from_lookup(&env_var, &NoSecrets, &skip).expect("invalid values are skipped")
+    }
+
+    /// Create a new config from environment variables, failing on invalid
+    /// values.
+    pub fn try_from_env() -> Result<Self, ConfigError> {
+        Self::from_env_with(&NoSecrets)
+    }
+
+    /// Create a new config from environment variables, asking `secrets`
+    /// for secret values first and failing on invalid values.
+    pub fn from_env_with(secrets: &dyn SecretProvider) -> Result<Self, ConfigError> {
+        Self::from_lookup(&env_var, secrets, &Err)
+    }
+
+    /// Create a new config from a `KEY=VALUE` file using the variable names
+    /// above.
+    ///
+    /// Variables missing from the file are read from the environment.
+    pub fn from_env_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        Self::from_env_file_with(path, &NoSecrets)
+    }
+
+    /// Create a new config from a `KEY=VALUE` file, asking `secrets` for
+    /// secret values first.
+    pub fn from_env_file_with(
+        path: impl AsRef<Path>,
+        secrets: &dyn SecretProvider,
+    ) -> Result<Self, ConfigFileError> {
+        let vars = read_env_file(path.as_ref())?;
+        let lookup = |name: &str| vars.get(name).cloned().or_else(|| env_var(name));
+        Self::from_lookup(&lookup, secrets, &Err).map_err(ConfigFileError::from)
+    }
+
+    /// Read the config through `lookup`, passing invalid values to
+    /// `on_error`, which either fails or lets them fall back to the default.
+    fn from_lookup(
+        lookup: Lookup<'_>,
+        secrets: &dyn SecretProvider,
+        on_error: &dyn Fn(ConfigError) -> Result<(), ConfigError>,
+    ) -> Result<Self, ConfigError> {
+        let data_roots = match lookup("DATA_ROOTS").map(|roots| roots.parse()) {
+            Some(Ok(roots)) => roots,
+            Some(Err(e)) => {
+                on_error(ConfigError::DataRoots(e))?;
+                DataRoots::default()
+            }
+            None => DataRoots::default(),
+        };
+        let secret = |name| match lookup_secret(name, lookup, secrets) {
+            Ok(secret) => Ok(secret),
+            Err(e) => on_error(ConfigError::Secret(e)).map(|()| None),
+        };
+        Ok(Self {
+            host: lookup("HOST").unwrap_or_else(|| "127.0.0.1".to_string()),
+            port: lookup("PORT").and_then(|p| p.parse().ok()).unwrap_or(3000),
+            data_path: lookup("DATA_PATH")
                 .map(PathBuf::from)
-                .unwrap_or_else(|_| PathBuf::from("./data")),
-            auth_token: std::env::var("AUTH_TOKEN").ok(),
-        }
+                .unwrap_or_else(|| PathBuf::from("./data")),
+            data_roots,
+            auth_token: secret("AUTH_TOKEN")?,
+            auth_token_hash: lookup("AUTH_TOKEN_HASH"),
+            auth_token_pepper: secret("AUTH_TOKEN_PEPPER")?,
+            log_filter: lookup("RUST_LOG"),
+            rate_limits: RateLimits::from_lookup(lookup),
+        })
     }
 
     /// Check if authentication is enabled.
     pub fn auth_enabled(&self) -> bool {
-        self.auth_token.is_some()
+        self.auth_token.is_some() || self.auth_token_hash.is_some()
     }
 
     /// Get the configured token or generate a new one.
     ///
-    /// Returns a tuple of (token, was_generated).
+    /// Returns a tuple of (token, was_generated). A configured
+    /// [`auth_token_hash`](Self::auth_token_hash) is not considered; use
+    /// [`resolve_token`](Self::resolve_token) when supporting hashed tokens.
     pub fn get_or_generate_token(&self) -> (String, bool) {
         match &self.auth_token {
-            Some(token) => (token.clone(), false),
+            Some(token) => (token.expose().to_string(), false),
             None => {
                 let token = generate_random_token();
                 (token, true)
@@ -73,6 +174,55 @@ impl BaseConfig {
         }
     }
 
+    /// Get the configured token, or generate a new one if authentication is
+    /// not configured at all.
+    ///
+    /// No token is generated when only an
+    /// [`auth_token_hash`](Self::auth_token_hash) is set, since clients hold
+    /// the token that hash was made from.
+    pub fn resolve_token(&self) -> AuthToken {
+        match (&self.auth_token, &self.auth_token_hash) {
+            (Some(token), _) => AuthToken::Configured(token.expose().to_string()),
+            (None, Some(_)) => AuthToken::Hashed,
+            (None, None) => AuthToken::Generated(generate_random_token()),
+        }
+    }
+
+    /// One-line overview for startup logs, without secrets.
+    ///
+    /// ```text
+    /// addr=127.0.0.1:3000 data_path=./data auth=token rate_limit.principal=unlimited rate_limit.session=10 rps
+    /// ```
+    pub fn summary(&self) -> String {
+        let auth = match (&self.auth_token, &self.auth_token_hash) {
+            (Some(_), Some(_)) => "token+hash",
+            (Some(_), None) => "token",
+            (None, Some(_)) => "hash",
+            (None, None) => "disabled",
+        };
+        let mut summary = format!(
+            "addr={} data_path={} auth={auth}",
+            self.socket_addr(),
+            self.data_path.display()
+        );
+        if !self.data_roots.is_empty() {
+            let roots: Vec<String> = self
+                .data_roots
+                .iter()
+                .map(|root| format!("{}({})", root.name, root.mode))
+                .collect();
+            summary.push_str(&format!(" data_roots={}", roots.join(",")));
+        }
+        if let Some(filter) = &self.log_filter {
+            summary.push_str(&format!(" log_filter={filter}"));
+        }
+        summary.push_str(&format!(
+            " rate_limit.principal={} rate_limit.session={}",
+            self.rate_limits.per_principal, self.rate_limits.per_session
+        ));
+        summary
+    }
+
     /// Get the socket address for binding.
     pub fn socket_addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
@@ -85,6 +235,92 @@ impl BaseConfig {
     pub fn resolve_data_path(&self, user_path: &str) -> Result<PathBuf, SafePathError> {
         safe_resolve(&self.data_path, user_path)
     }
+
+    /// Safely resolve a user-provided path to a file to create within the
+    /// data directory.
+    ///
+    /// See [`safe_resolve_for_create`] for how paths that do not exist yet
+    /// are checked.
+    pub fn resolve_data_path_for_create(
+        &self,
+        user_path: &str,
+        create_parents: bool,
+    ) -> Result<PathBuf, SafePathError> {
+        safe_resolve_for_create(&self.data_path, user_path, create_parents)
+    }
+
+    /// Safely resolve a `root:path` reference within the named
+    /// [`data_roots`](Self::data_roots), enforcing the root's access mode.
+    pub fn resolve_root_path(
+        &self,
+        reference: &str,
+        access: Access,
+    ) -> Result<PathBuf, SafePathError> {
+        self.data_roots.resolve(reference, access)
+    }
+
+    /// Open the data directory as a [`SafeDir`], to open files beneath it
+    /// without a window between checking a path and using it.
+    #[cfg(unix)]
+    pub fn open_data_dir(&self) -> Result<SafeDir, SafePathError> {
+        SafeDir::open(&self.data_path)
+    }
+
+    /// Open the data directory for sandboxed file operations.
+    #[cfg(unix)]
+    pub fn data_dir(&self) -> Result<DataDir, DataDirError> {
+        DataDir::open(&self.data_path)
+    }
+}
+
+/// Token returned by [`BaseConfig::resolve_token`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthToken {
+    /// The configured `AUTH_TOKEN`.
+    Configured(String),
+    /// Only `AUTH_TOKEN_HASH` is configured, so there is no plaintext token.
+    Hashed,
+    /// A random token, as authentication is not configured.
+    Generated(String),
+}
+
+/// Error returned when a [`BaseConfig`] cannot be created.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A secret could not be read.
+    Secret(SecretError),
+    /// `DATA_ROOTS` is invalid.
+    DataRoots(DataRootsParseError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Secret(e) => e.fmt(f),
+            Self::DataRoots(e) => write!(f, "DATA_ROOTS: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Secret(e) => Some(e),
+            Self::DataRoots(e) => Some(e),
+        }
+    }
+}
+
+impl From<SecretError> for ConfigError {
+    fn from(e: SecretError) -> Self {
+        Self::Secret(e)
+    }
+}
+
+impl From<DataRootsParseError> for ConfigError {
+    fn from(e: DataRootsParseError) -> Self {
+        Self::DataRoots(e)
+    }
 }
 
 impl Default for BaseConfig {
@@ -96,6 +332,7 @@ impl Default for BaseConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::AccessMode;
 
     #[test]
     fn test_default_values() {
@@ -104,6 +341,7 @@ mod tests {
         std::env::remove_var("PORT");
         std::env::remove_var("DATA_PATH");
         std::env::remove_var("AUTH_TOKEN");
+        std::env::remove_var("AUTH_TOKEN_HASH");
 
         let config = BaseConfig::from_env();
         assert_eq!(config.host, "127.0.0.1");
@@ -113,28 +351,114 @@ mod tests {
         assert!(!config.auth_enabled());
     }
 
+    #[test]
+    fn test_from_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.env");
+        std::fs::write(
+            &path,
+            "PORT=8080\nRATE_LIMIT_SESSION_IN_FLIGHT=2\nDATA_ROOTS=docs=/srv/docs:rw\n",
+        )
+        .unwrap();
+
+        let config = BaseConfig::from_env_file(&path).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.rate_limits.per_session.max_in_flight, Some(2));
+        assert_eq!(config.data_roots.get("docs").unwrap().mode, AccessMode::Rw);
+
+        std::fs::write(&path, "DATA_ROOTS=docs=/srv/docs:RW\n").unwrap();
+        assert!(matches!(
+            BaseConfig::from_env_file(&path),
+            Err(ConfigFileError::DataRoots(e)) if e.entry == "docs=/srv/docs:RW"
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            BaseConfig::from_env_file(&path),
+            Err(ConfigFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_skips_invalid_values() {
+        let lookup = |name: &str| match name {
+            "PORT" => Some("8080".to_string()),
+            "DATA_ROOTS" => Some("docs=/srv/docs:RW".to_string()),
+            "AUTH_TOKEN_FILE" => Some("/nonexistent/token".to_string()),
+            _ => None,
+        };
+        let skip = |_| Ok(());
+        let config = BaseConfig::from_lookup(&lookup, &NoSecrets, &skip).unwrap();
+        assert_eq!(config.port, 8080);
+        assert!(config.data_roots.get("docs").is_none());
+        assert!(config.auth_token.is_none());
+
+        assert!(BaseConfig::from_lookup(&lookup, &NoSecrets, &Err).is_err());
+    }
+
     #[test]
     fn test_socket_addr() {
         let config = BaseConfig {
             host: "0.0.0.0".to_string(),
             port: 8080,
             data_path: PathBuf::from("./data"),
+            data_roots: DataRoots::default(),
             auth_token: None,
+            auth_token_hash: None,
+            auth_token_pepper: None,
+            log_filter: None,
+            rate_limits: RateLimits::default(),
         };
         assert_eq!(config.socket_addr(), "0.0.0.0:8080");
     }
 
+    #[test]
+    fn test_secrets_are_redacted() {
+        let config = BaseConfig {
+            host: "127.0.0.1".to_string(),
+            port: 3000,
+            data_path: PathBuf::from("./data"),
+            data_roots: DataRoots::default(),
+            auth_token: Some(Secret::new("hunter2")),
+            auth_token_hash: None,
+            auth_token_pepper: Some(Secret::new("s3cret-pepper")),
+            log_filter: None,
+            rate_limits: RateLimits::default(),
+        };
+        let debug = format!("{config:?}");
+        let json = serde_json::to_string(&config).unwrap();
+        for output in [&debug, &json, &config.summary()] {
+            assert!(!output.contains("hunter2"), "{output}");
+            assert!(!output.contains("s3cret-pepper"), "{output}");
+        }
+        assert!(json.contains(r#""auth_token":"[REDACTED]""#));
+        assert_eq!(
+            config.summary(),
+            "addr=127.0.0.1:3000 data_path=./data auth=token \
+             rate_limit.principal=unlimited rate_limit.session=unlimited"
+        );
+    }
+
     #[test]
     fn test_get_or_generate_token_with_existing() {
         let config = BaseConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             data_path: PathBuf::from("./data"),
-            auth_token: Some("my-token".to_string()),
+            data_roots: DataRoots::default(),
+            auth_token: Some(Secret::new("my-token")),
+            auth_token_hash: None,
+            auth_token_pepper: None,
+            log_filter: None,
+            rate_limits: RateLimits::default(),
         };
         let (token, generated) = config.get_or_generate_token();
         assert_eq!(token, "my-token");
         assert!(!generated);
+        assert_eq!(
+            config.resolve_token(),
+            AuthToken::Configured("my-token".to_string())
+        );
     }
 
     #[test]
@@ -143,10 +467,47 @@ mod tests {
             host: "127.0.0.1".to_string(),
             port: 3000,
             data_path: PathBuf::from("./data"),
+            data_roots: DataRoots::default(),
             auth_token: None,
+            auth_token_hash: None,
+            auth_token_pepper: None,
+            log_filter: None,
+            rate_limits: RateLimits::default(),
         };
         let (token, generated) = config.get_or_generate_token();
         assert_eq!(token.len(), 32);
         assert!(generated);
+        match config.resolve_token() {
+            AuthToken::Generated(token) => assert_eq!(token.len(), 32),
+            token => panic!("expected a generated token, got {token:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_token_with_hash() {
+        let config = BaseConfig {
+            host: "127.0.0.1".to_string(),
+            port: 3000,
+            data_path: PathBuf::from("./data"),
+            data_roots: DataRoots::default(),
+            auth_token: None,
+            // `hash_token("my-token", "")` with a fixed salt.
+            auth_token_hash: Some(
+                "sha256$000102030405060708090a0b0c0d0e0f$\
+                 4ed323b2cd14b91138543e987fd054d300555554806eddb8b98fa02bbfb41951"
+                    .to_string(),
+            ),
+            auth_token_pepper: None,
+            log_filter: None,
+            rate_limits: RateLimits::default(),
+        };
+        assert!(config.auth_enabled());
+        assert_eq!(config.resolve_token(), AuthToken::Hashed);
+        #[cfg(feature = "auth")]
+        {
+            let tokens = crate::auth::TokenStore::from_config(&config).unwrap();
+            assert_eq!(tokens.authenticate("my-token").unwrap().name(), "default");
+            assert!(tokens.authenticate("other").is_none());
+        }
     }
 }
@@ -0,0 +1,399 @@
+//! Sandboxed file operations for MCP tools.
+
+use std::{
+    fmt,
+    fs::Metadata,
+    io::{self, Read, Write},
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::safe_dir::{EntryKind, SafeDir, SafeDirEntry};
+use super::safe_path::SafePathError;
+
+/// File operations confined to a data directory.
+///
+/// Every operation takes a user-provided path relative to the directory and
+/// goes through [`SafeDir`], so tool code never handles raw paths. Writes are
+/// atomic: content goes to a temporary file that is renamed into place.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use mcp_core::config::DataDir;
+///
+/// let data = DataDir::open("/srv/data")?
+///     .max_file_size(1 << 20)
+///     .quota(100 << 20);
+/// data.mkdir("notes")?;
+/// data.write("notes/today.md", b"# Today")?;
+/// for entry in data.list("notes")? {
+///     println!("{:?} {}", entry.name, entry.len);
+/// }
+/// # Ok::<(), mcp_core::config::DataDirError>(())
+/// ```
+#[derive(Debug)]
+pub struct DataDir {
+    dir: SafeDir,
+    read_only: bool,
+    max_file_size: Option<u64>,
+    quota: Option<u64>,
+}
+
+/// Distinguishes temporary files of concurrent writes in this process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl DataDir {
+    /// Open the directory at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DataDirError> {
+        Ok(Self::from_safe_dir(SafeDir::open(path)?))
+    }
+
+    /// Operate on an already open directory.
+    pub fn from_safe_dir(dir: SafeDir) -> Self {
+        Self {
+            dir,
+            read_only: false,
+            max_file_size: None,
+            quota: None,
+        }
+    }
+
+    /// Refuse all modifications.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Refuse to read or write files larger than `bytes`.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Refuse writes that would grow the files in the directory beyond
+    /// `bytes` in total.
+    ///
+    /// Usage is computed by walking the directory on each write.
+    pub fn quota(mut self, bytes: u64) -> Self {
+        self.quota = Some(bytes);
+        self
+    }
+
+    /// Whether modifications are refused.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The underlying directory handle.
+    pub fn safe_dir(&self) -> &SafeDir {
+        &self.dir
+    }
+
+    /// Read a whole file.
+    pub fn read(&self, user_path: &str) -> Result<Vec<u8>, DataDirError> {
+        let file = self.dir.open_file(user_path)?;
+        let len = file.metadata()?.len();
+        self.check_size(len)?;
+        let mut contents = Vec::with_capacity(len as usize);
+        // Guard against the file growing after the check.
+        let limit = self.max_file_size.map_or(u64::MAX, |max| max + 1);
+        file.take(limit).read_to_end(&mut contents)?;
+        self.check_size(contents.len() as u64)?;
+        Ok(contents)
+    }
+
+    /// Read a whole file as UTF-8.
+    pub fn read_to_string(&self, user_path: &str) -> Result<String, DataDirError> {
+        String::from_utf8(self.read(user_path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+    }
+
+    /// Replace the contents of a file atomically, creating it if needed.
+    ///
+    /// The parent directory must exist; see [`mkdir`](Self::mkdir).
+    pub fn write(&self, user_path: &str, contents: &[u8]) -> Result<(), DataDirError> {
+        self.check_writable()?;
+        self.check_size(contents.len() as u64)?;
+        if let Some(quota) = self.quota {
+            let replaced = match self.dir.metadata(user_path) {
+                Ok(metadata) if metadata.is_file() => metadata.len(),
+                _ => 0,
+            };
+            let used = self.usage()?;
+            let needed = (used - replaced.min(used)).saturating_add(contents.len() as u64);
+            if needed > quota {
+                return Err(DataDirError::QuotaExceeded { needed, quota });
+            }
+        }
+
+        let temp = temp_path(user_path);
+        let result = self
+            .dir
+            .open_at(
+                &temp,
+                libc::O_WRONLY | libc::O_CREAT | libc::O_EXCL | libc::O_NOFOLLOW,
+            )
+            .map_err(DataDirError::from)
+            .and_then(|fd| {
+                let mut file = std::fs::File::from(fd);
+                file.write_all(contents)?;
+                file.sync_all()?;
+                Ok(())
+            })
+            .and_then(|()| Ok(self.dir.rename(&temp, user_path)?));
+        if result.is_err() {
+            let _ = self.dir.remove_file(&temp);
+        }
+        result
+    }
+
+    /// The entries of a directory; `""` lists the data directory itself.
+    pub fn list(&self, user_path: &str) -> Result<Vec<SafeDirEntry>, DataDirError> {
+        let mut entries = self.dir.read_dir(user_path)?;
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Metadata of a file or directory.
+    pub fn stat(&self, user_path: &str) -> Result<Metadata, DataDirError> {
+        Ok(self.dir.metadata(user_path)?)
+    }
+
+    /// Delete a file or an empty directory.
+    pub fn delete(&self, user_path: &str) -> Result<(), DataDirError> {
+        self.check_writable()?;
+        match self.dir.remove_file(user_path) {
+            Err(SafePathError::NotFound(e)) if is_dir_error(&e) => {
+                Ok(self.dir.remove_dir(user_path)?)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Create a directory and its missing parents.
+    pub fn mkdir(&self, user_path: &str) -> Result<(), DataDirError> {
+        self.check_writable()?;
+        Ok(self.dir.create_dir_all(user_path)?)
+    }
+
+    /// Move a file or directory, replacing `to` if it is a file.
+    pub fn rename(&self, from: &str, to: &str) -> Result<(), DataDirError> {
+        self.check_writable()?;
+        Ok(self.dir.rename(from, to)?)
+    }
+
+    /// Total size of the files in the directory.
+    pub fn usage(&self) -> Result<u64, DataDirError> {
+        let mut total = 0;
+        let mut pending = vec![String::new()];
+        while let Some(dir) = pending.pop() {
+            for entry in self.dir.read_dir(&dir)? {
+                match entry.kind {
+                    EntryKind::File => total += entry.len,
+                    EntryKind::Dir => {
+                        let name = entry.name.to_string_lossy();
+                        pending.push(if dir.is_empty() {
+                            name.into_owned()
+                        } else {
+                            format!("{dir}/{name}")
+                        });
+                    }
+                    EntryKind::Symlink | EntryKind::Other => {}
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    fn check_writable(&self) -> Result<(), DataDirError> {
+        if self.read_only {
+            return Err(DataDirError::ReadOnly);
+        }
+        Ok(())
+    }
+
+    fn check_size(&self, size: u64) -> Result<(), DataDirError> {
+        match self.max_file_size {
+            Some(limit) if size > limit => Err(DataDirError::TooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A hidden sibling of `user_path` for writing before the rename.
+fn temp_path(user_path: &str) -> String {
+    let (parent, name) = match user_path.rsplit_once('/') {
+        Some((parent, name)) => (format!("{parent}/"), name),
+        None => (String::new(), user_path),
+    };
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{parent}.{name}.{}.{n}.tmp", std::process::id())
+}
+
+/// `unlink` on a directory fails with `EISDIR` on Linux and `EPERM` elsewhere.
+fn is_dir_error(e: &io::Error) -> bool {
+    matches!(e.raw_os_error(), Some(libc::EISDIR | libc::EPERM))
+}
+
+/// Error returned by [`DataDir`] operations.
+#[derive(Debug)]
+pub enum DataDirError {
+    /// The path is invalid, escapes the directory, or does not exist.
+    Path(SafePathError),
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// The directory is read-only.
+    ReadOnly,
+    /// The file is larger than the configured maximum.
+    TooLarge {
+        /// Size of the file in bytes.
+        size: u64,
+        /// Maximum file size in bytes.
+        limit: u64,
+    },
+    /// The write would exceed the directory's quota.
+    QuotaExceeded {
+        /// Bytes the directory would use after the write.
+        needed: u64,
+        /// Quota in bytes.
+        quota: u64,
+    },
+}
+
+impl fmt::Display for DataDirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(e) => e.fmt(f),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::ReadOnly => write!(f, "data directory is read-only"),
+            Self::TooLarge { size, limit } => {
+                write!(f, "file of {size} bytes exceeds the limit of {limit} bytes")
+            }
+            Self::QuotaExceeded { needed, quota } => {
+                write!(
+                    f,
+                    "write needs {needed} bytes, over the quota of {quota} bytes"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataDirError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Path(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::ReadOnly | Self::TooLarge { .. } | Self::QuotaExceeded { .. } => None,
+        }
+    }
+}
+
+impl From<SafePathError> for DataDirError {
+    fn from(e: SafePathError) -> Self {
+        Self::Path(e)
+    }
+}
+
+impl From<io::Error> for DataDirError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use std::fs;
+
+    fn setup_dir() -> (tempfile::TempDir, DataDir) {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        let data = DataDir::open(dir.path()).unwrap();
+        (dir, data)
+    }
+
+    fn names(entries: &[SafeDirEntry]) -> Vec<OsString> {
+        entries.iter().map(|entry| entry.name.clone()).collect()
+    }
+
+    #[test]
+    fn reads_writes_and_lists() {
+        let (dir, data) = setup_dir();
+        assert_eq!(data.read_to_string("a.txt").unwrap(), "hello");
+
+        data.mkdir("notes/2024").unwrap();
+        data.write("notes/2024/b.md", b"first").unwrap();
+        data.write("notes/2024/b.md", b"second").unwrap();
+        assert_eq!(data.read("notes/2024/b.md").unwrap(), b"second");
+        assert_eq!(names(&data.list("notes/2024").unwrap()), ["b.md"]);
+        assert_eq!(names(&data.list("").unwrap()), ["a.txt", "notes"]);
+        assert_eq!(data.stat("notes/2024/b.md").unwrap().len(), 6);
+
+        data.rename("notes/2024/b.md", "b.md").unwrap();
+        assert!(dir.path().join("b.md").exists());
+        data.delete("notes/2024").unwrap();
+        data.delete("b.md").unwrap();
+        assert_eq!(names(&data.list("notes").unwrap()), Vec::<OsString>::new());
+        assert!(matches!(
+            data.read("b.md"),
+            Err(DataDirError::Path(SafePathError::NotFound(_)))
+        ));
+        assert!(matches!(
+            data.read("notes"),
+            Err(DataDirError::Path(SafePathError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn stays_inside_the_directory() {
+        let (_dir, data) = setup_dir();
+        for result in [
+            data.write("../escape.txt", b"x"),
+            data.rename("a.txt", "../a.txt"),
+            data.delete("../a.txt"),
+            data.mkdir("/tmp/x"),
+        ] {
+            assert!(matches!(
+                result,
+                Err(DataDirError::Path(SafePathError::PathTraversal))
+            ));
+        }
+    }
+
+    #[test]
+    fn enforces_read_only_and_limits() {
+        let (dir, data) = setup_dir();
+        let data = data.read_only(true);
+        assert!(matches!(
+            data.write("b.txt", b"x"),
+            Err(DataDirError::ReadOnly)
+        ));
+        assert!(matches!(data.delete("a.txt"), Err(DataDirError::ReadOnly)));
+        assert!(data.read("a.txt").is_ok());
+
+        let data = DataDir::open(dir.path()).unwrap().max_file_size(4).quota(8);
+        assert!(matches!(
+            data.read("a.txt"),
+            Err(DataDirError::TooLarge { size: 5, limit: 4 })
+        ));
+        assert!(matches!(
+            data.write("b.txt", b"12345"),
+            Err(DataDirError::TooLarge { .. })
+        ));
+        data.write("b.txt", b"123").unwrap();
+        assert!(matches!(
+            data.write("c.txt", b"1"),
+            Err(DataDirError::QuotaExceeded {
+                needed: 9,
+                quota: 8
+            })
+        ));
+        // Replacing a file only counts the difference.
+        data.write("b.txt", b"12").unwrap();
+        assert_eq!(data.usage().unwrap(), 7);
+        assert_eq!(names(&data.list("").unwrap()), ["a.txt", "b.txt"]);
+    }
+}
@@ -0,0 +1,298 @@
+//! Named data directories with an access mode each.
+
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use serde::Serialize;
+
+#[cfg(unix)]
+use super::data_dir::{DataDir, DataDirError};
+use super::safe_path::{safe_resolve, safe_resolve_for_create, SafePathError};
+
+/// Whether a data root may be written to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessMode {
+    /// Read-only.
+    #[default]
+    Ro,
+    /// Read-write.
+    Rw,
+}
+
+impl fmt::Display for AccessMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ro => write!(f, "ro"),
+            Self::Rw => write!(f, "rw"),
+        }
+    }
+}
+
+/// What a path is resolved for, checked against the root's [`AccessMode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Reading an existing file or directory.
+    Read,
+    /// Creating or modifying a file, which need not exist yet.
+    Write,
+}
+
+/// A named data directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DataRoot {
+    /// Name used in `root:path` references.
+    pub name: String,
+    /// Base directory.
+    pub path: PathBuf,
+    /// Whether the directory may be written to.
+    pub mode: AccessMode,
+}
+
+impl DataRoot {
+    /// Safely resolve `user_path` within this root for `access`.
+    pub fn resolve(&self, user_path: &str, access: Access) -> Result<PathBuf, SafePathError> {
+        match access {
+            Access::Read => safe_resolve(&self.path, user_path),
+            Access::Write if self.mode == AccessMode::Ro => {
+                Err(SafePathError::ReadOnly(self.name.clone()))
+            }
+            Access::Write => safe_resolve_for_create(&self.path, user_path, false),
+        }
+    }
+
+    /// Open the root for sandboxed file operations, read-only unless its
+    /// mode is `rw`.
+    #[cfg(unix)]
+    pub fn data_dir(&self) -> Result<DataDir, DataDirError> {
+        Ok(DataDir::open(&self.path)?.read_only(self.mode == AccessMode::Ro))
+    }
+}
+
+/// Named data roots, from `DATA_ROOTS`.
+///
+/// The variable is a comma-separated list of `name=path[:mode]` entries,
+/// where `mode` is `ro` (the default) or `rw`:
+///
+/// ```text
+/// DATA_ROOTS=corpus=/srv/corpus:ro,scratch=/tmp/scratch:rw
+/// ```
+///
+/// Any other alphanumeric suffix, such as `:RW` or `:rx`, is rejected rather
+/// than taken as part of the path. Paths ending in such a suffix are quoted
+/// (`odd="/srv/odd:v2":ro`) or escape the colon (`odd=/srv/odd\:v2`).
+///
+/// Paths are referenced as `root:path`, e.g. `corpus:2024/index.html`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct DataRoots(Vec<DataRoot>);
+
+impl DataRoots {
+    /// The root called `name`.
+    pub fn get(&self, name: &str) -> Option<&DataRoot> {
+        self.0.iter().find(|root| root.name == name)
+    }
+
+    /// All roots, in configuration order.
+    pub fn iter(&self) -> impl Iterator<Item = &DataRoot> {
+        self.0.iter()
+    }
+
+    /// Whether no roots are configured.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Safely resolve a `root:path` reference for `access`.
+    ///
+    /// Unknown roots are rejected with [`SafePathError::UnknownRoot`], writes
+    /// to `ro` roots with [`SafePathError::ReadOnly`]; the path is then
+    /// checked as by [`safe_resolve`] (or [`safe_resolve_for_create`] for
+    /// writes).
+    pub fn resolve(&self, reference: &str, access: Access) -> Result<PathBuf, SafePathError> {
+        let (name, user_path) = reference.split_once(':').unwrap_or((reference, ""));
+        let root = self
+            .get(name)
+            .ok_or_else(|| SafePathError::UnknownRoot(name.to_string()))?;
+        root.resolve(user_path, access)
+    }
+}
+
+impl FromStr for DataRoots {
+    type Err = DataRootsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut roots: Vec<DataRoot> = Vec::new();
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let invalid = |reason: &str| DataRootsParseError {
+                entry: entry.to_string(),
+                reason: reason.to_string(),
+            };
+            let (name, rest) = entry
+                .split_once('=')
+                .ok_or_else(|| invalid("expected name=path"))?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(':') {
+                return Err(invalid("invalid root name"));
+            }
+            if roots.iter().any(|root| root.name == name) {
+                return Err(invalid("duplicate root name"));
+            }
+            let (path, mode) = split_mode(rest).map_err(invalid)?;
+            if path.is_empty() {
+                return Err(invalid("missing path"));
+            }
+            roots.push(DataRoot {
+                name: name.to_string(),
+                path: PathBuf::from(path),
+                mode,
+            });
+        }
+        Ok(Self(roots))
+    }
+}
+
+/// Split `path[:mode]`, unquoting or unescaping the path.
+fn split_mode(rest: &str) -> Result<(String, AccessMode), &'static str> {
+    let mode = |suffix: &str| match suffix {
+        "ro" => Ok(AccessMode::Ro),
+        "rw" => Ok(AccessMode::Rw),
+        _ => Err("unknown access mode, expected ro or rw"),
+    };
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let (path, tail) = quoted.split_once('"').ok_or("unterminated quote")?;
+        let mode = match tail {
+            "" => AccessMode::default(),
+            tail => mode(
+                tail.strip_prefix(':')
+                    .ok_or("unexpected text after quote")?,
+            )?,
+        };
+        return Ok((path.to_string(), mode));
+    }
+    let colon = rest
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| c == ':' && !rest[..i].ends_with('\\'))
+        .map(|(i, _)| i);
+    let (path, mode) = match colon {
+        Some(i) if is_mode_like(&rest[i + 1..]) => (&rest[..i], mode(&rest[i + 1..])?),
+        _ => (rest, AccessMode::default()),
+    };
+    Ok((path.replace("\\:", ":"), mode))
+}
+
+/// Whether `suffix` is meant as an access mode rather than part of a path,
+/// e.g. `rw` but not `/data` in `C:/data`.
+fn is_mode_like(suffix: &str) -> bool {
+    !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Error returned for an invalid `DATA_ROOTS` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataRootsParseError {
+    /// The offending entry.
+    pub entry: String,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+impl fmt::Display for DataRootsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid data root `{}`: {}", self.entry, self.reason)
+    }
+}
+
+impl std::error::Error for DataRootsParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn parses_roots() {
+        let roots: DataRoots = "corpus=/srv/corpus:ro, scratch=/tmp/scratch:rw,c=C:/data"
+            .parse()
+            .unwrap();
+        let names: Vec<_> = roots
+            .iter()
+            .map(|root| (root.name.as_str(), root.mode))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("corpus", AccessMode::Ro),
+                ("scratch", AccessMode::Rw),
+                ("c", AccessMode::Ro)
+            ]
+        );
+        assert_eq!(roots.get("c").unwrap().path, PathBuf::from("C:/data"));
+        assert!("".parse::<DataRoots>().unwrap().is_empty());
+
+        for value in [
+            "corpus",
+            "=/srv",
+            "a=",
+            "a=/x,a=/y",
+            "a:b=/x",
+            "a=/x:RW",
+            "a=/x:rx",
+            "a=\"/x",
+            "a=\"/x\":wr",
+            "a=\"/x\"/y",
+        ] {
+            assert!(value.parse::<DataRoots>().is_err(), "{value}");
+        }
+
+        let roots: DataRoots = r#"a="/srv/a:v2",b="/srv/b:v2":rw,c=/srv/c\:v2:rw,d=/srv/d:"#
+            .parse()
+            .unwrap();
+        let paths: Vec<_> = roots
+            .iter()
+            .map(|root| (root.path.to_str().unwrap(), root.mode))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("/srv/a:v2", AccessMode::Ro),
+                ("/srv/b:v2", AccessMode::Rw),
+                ("/srv/c:v2", AccessMode::Rw),
+                ("/srv/d:", AccessMode::Ro)
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_references_with_access_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("corpus")).unwrap();
+        fs::create_dir(dir.path().join("scratch")).unwrap();
+        fs::write(dir.path().join("corpus/index.html"), "<html>").unwrap();
+        let roots: DataRoots = format!(
+            "corpus={}:ro,scratch={}:rw",
+            dir.path().join("corpus").display(),
+            dir.path().join("scratch").display()
+        )
+        .parse()
+        .unwrap();
+
+        assert!(roots.resolve("corpus:index.html", Access::Read).is_ok());
+        assert!(roots.resolve("scratch:new.txt", Access::Write).is_ok());
+        assert!(matches!(
+            roots.resolve("corpus:index.html", Access::Write),
+            Err(SafePathError::ReadOnly(name)) if name == "corpus"
+        ));
+        assert!(matches!(
+            roots.resolve("other:index.html", Access::Read),
+            Err(SafePathError::UnknownRoot(name)) if name == "other"
+        ));
+        assert!(matches!(
+            roots.resolve("scratch:../corpus/index.html", Access::Write),
+            Err(SafePathError::PathTraversal)
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+//! `KEY=VALUE` config files using the environment variable names.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use super::base::ConfigError;
+use super::data_roots::DataRootsParseError;
+use super::secret::SecretError;
+
+/// Source of configuration variables by name.
+pub(crate) type Lookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Read a config file into its variables.
+///
+/// Lines are `KEY=VALUE`, optionally prefixed with `export`. Blank lines and
+/// lines starting with `#` are skipped; a value wrapped in matching single or
+/// double quotes is unquoted.
+pub(crate) fn read_env_file(path: &Path) -> Result<HashMap<String, String>, ConfigFileError> {
+    parse_env_file(&fs::read_to_string(path).map_err(ConfigFileError::Io)?)
+}
+
+fn parse_env_file(contents: &str) -> Result<HashMap<String, String>, ConfigFileError> {
+    let mut vars = HashMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |reason: &str| ConfigFileError::Invalid {
+            line: index + 1,
+            reason: reason.to_string(),
+        };
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid("expected KEY=VALUE"))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(invalid("invalid variable name"));
+        }
+        vars.insert(key.to_string(), unquote(value.trim()).to_string());
+    }
+    Ok(vars)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Error returned when reading a config file fails.
+#[derive(Debug)]
+pub enum ConfigFileError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The line (1-based) is not a valid `KEY=VALUE` pair.
+    Invalid {
+        /// Line number.
+        line: usize,
+        /// What is wrong with it.
+        reason: String,
+    },
+    /// A secret the file refers to could not be read.
+    Secret(SecretError),
+    /// `DATA_ROOTS` is invalid.
+    DataRoots(DataRootsParseError),
+}
+
+impl From<ConfigError> for ConfigFileError {
+    fn from(e: ConfigError) -> Self {
+        match e {
+            ConfigError::Secret(e) => Self::Secret(e),
+            ConfigError::DataRoots(e) => Self::DataRoots(e),
+        }
+    }
+}
+
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read config file: {e}"),
+            Self::Invalid { line, reason } => write!(f, "config file line {line}: {reason}"),
+            Self::Secret(e) => e.fmt(f),
+            Self::DataRoots(e) => write!(f, "DATA_ROOTS: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Invalid { .. } => None,
+            Self::Secret(e) => Some(e),
+            Self::DataRoots(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_env_files() {
+        let vars = parse_env_file(
+            "# server\nHOST=0.0.0.0\nexport PORT = 8080\n\nAUTH_TOKEN=\"a b\"\nRUST_LOG='info'\n",
+        )
+        .unwrap();
+        assert_eq!(vars["HOST"], "0.0.0.0");
+        assert_eq!(vars["PORT"], "8080");
+        assert_eq!(vars["AUTH_TOKEN"], "a b");
+        assert_eq!(vars["RUST_LOG"], "info");
+        assert_eq!(vars.len(), 4);
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for (contents, line) in [("HOST=a\nPORT", 2), ("=value", 1), ("A B=c", 1)] {
+            assert!(
+                matches!(
+                    parse_env_file(contents),
+                    Err(ConfigFileError::Invalid { line: l, .. }) if l == line
+                ),
+                "{contents}"
+            );
+        }
+    }
+}
@@ -1,9 +1,32 @@
 //! Configuration management with environment variable support.
 
 mod base;
+#[cfg(unix)]
+mod data_dir;
+mod data_roots;
+mod env_file;
+mod path_policy;
+mod rate_limit;
+#[cfg(feature = "reload")]
+mod reload;
+#[cfg(unix)]
+mod safe_dir;
 pub mod safe_path;
+mod secret;
 mod token;
 
-pub use base::BaseConfig;
-pub use safe_path::{safe_resolve, SafePathError};
+pub use base::{AuthToken, BaseConfig, ConfigError};
+#[cfg(unix)]
+pub use data_dir::{DataDir, DataDirError};
+pub use data_roots::{Access, AccessMode, DataRoot, DataRoots, DataRootsParseError};
+pub use env_file::ConfigFileError;
+pub use rate_limit::{RateLimit, RateLimits};
+#[cfg(feature = "reload")]
+pub use reload::{watch_config, watch_config_with};
+#[cfg(unix)]
+pub use safe_dir::{EntryKind, SafeDir, SafeDirEntry};
+pub use safe_path::{
+    safe_resolve, safe_resolve_for_create, safe_resolve_with, PathPolicy, SafePathError,
+};
+pub use secret::{Secret, SecretError, SecretProvider};
 pub use token::generate_random_token;
@@ -0,0 +1,383 @@
+//! Rules for which paths inside a base directory may be accessed.
+
+use std::path::{Component, Path};
+
+use super::safe_path::SafePathError;
+
+/// Restrictions checked by [`safe_resolve_with`](super::safe_resolve_with)
+/// on top of staying inside the base directory.
+///
+/// Patterns are globs where `*` matches within a path segment, `**` across
+/// segments and `?` a single character. A pattern with `/` is matched
+/// against the whole path relative to the base. A deny pattern without `/`
+/// is matched against each segment, so `.git` denies everything below a
+/// `.git` directory, while an allow pattern without `/` is matched against
+/// the file name only, so `*.md` does not allow everything below a directory
+/// named `notes.md`.
+///
+/// Allow patterns and extensions restrict files. Directories only have to
+/// pass the other checks, so an [`allow_extension`](Self::allow_extension)
+/// still lets static file handlers resolve a directory to its index file.
+///
+/// All checks except the length apply to the resolved path, so a symlink or
+/// `..` cannot be used to reach a denied file under another name.
+///
+/// # Example
+///
+/// ```rust
+/// use mcp_core::config::safe_path::PathPolicy;
+///
+/// let policy = PathPolicy::new()
+///     .deny_hidden(true)
+///     .deny_pattern("*.swp")
+///     .deny_extension("key")
+///     .max_depth(8);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PathPolicy {
+    deny_hidden: bool,
+    deny_symlinks: bool,
+    allow: Vec<String>,
+    deny: Vec<String>,
+    allow_extensions: Vec<String>,
+    deny_extensions: Vec<String>,
+    max_depth: Option<usize>,
+    max_len: Option<usize>,
+}
+
+impl PathPolicy {
+    /// A policy without restrictions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deny paths with a segment starting with `.`, such as `.env` or
+    /// `.git/config`.
+    pub fn deny_hidden(mut self, deny: bool) -> Self {
+        self.deny_hidden = deny;
+        self
+    }
+
+    /// Deny paths that pass through a symlink, even one inside the base.
+    pub fn deny_symlinks(mut self, deny: bool) -> Self {
+        self.deny_symlinks = deny;
+        self
+    }
+
+    /// Only allow files matching one of the allow patterns.
+    pub fn allow_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.allow.push(pattern.into());
+        self
+    }
+
+    /// Deny paths matching `pattern`.
+    pub fn deny_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.deny.push(pattern.into());
+        self
+    }
+
+    /// Only allow files with one of the allowed extensions (case-insensitive,
+    /// without the dot).
+    pub fn allow_extension(mut self, extension: impl Into<String>) -> Self {
+        self.allow_extensions
+            .push(extension.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Deny files with `extension` (case-insensitive, without the dot).
+    pub fn deny_extension(mut self, extension: impl Into<String>) -> Self {
+        self.deny_extensions
+            .push(extension.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Deny paths with more than `depth` segments.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Deny user paths longer than `len` bytes.
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = Some(len);
+        self
+    }
+
+    /// Check a user path before it is resolved.
+    pub(crate) fn check_user_path(&self, user_path: &str) -> Result<(), SafePathError> {
+        match self.max_len {
+            Some(max) if user_path.len() > max => Err(SafePathError::TooLong {
+                len: user_path.len(),
+                max,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether symlinks along the path must be rejected.
+    pub(crate) fn denies_symlinks(&self) -> bool {
+        self.deny_symlinks
+    }
+
+    /// Check a resolved directory, relative to the base, before listing the
+    /// files below it.
+    ///
+    /// Only rejects directories below which every path is denied: those at
+    /// the maximum depth, hidden ones and those with a segment matching a
+    /// deny pattern without `/`.
+    #[cfg_attr(not(feature = "resources"), allow(dead_code))]
+    pub(crate) fn check_dir(&self, relative: &Path) -> Result<(), SafePathError> {
+        let segments = segments(relative);
+        if let Some(max) = self.max_depth {
+            if segments.len() >= max {
+                return Err(SafePathError::TooDeep {
+                    depth: segments.len() + 1,
+                    max,
+                });
+            }
+        }
+        if self.deny_hidden && segments.iter().any(|segment| segment.starts_with('.')) {
+            return Err(SafePathError::Hidden);
+        }
+        if let Some(pattern) = self.deny.iter().find(|pattern| {
+            !pattern.contains('/') && segments.iter().any(|segment| glob_match(pattern, segment))
+        }) {
+            return Err(SafePathError::DeniedPattern(pattern.clone()));
+        }
+        Ok(())
+    }
+
+    /// Check a resolved path, relative to the base.
+    ///
+    /// Allow lists and denied extensions only apply to files.
+    pub(crate) fn check(&self, relative: &Path, is_dir: bool) -> Result<(), SafePathError> {
+        let segments = segments(relative);
+
+        if let Some(max) = self.max_depth {
+            if segments.len() > max {
+                return Err(SafePathError::TooDeep {
+                    depth: segments.len(),
+                    max,
+                });
+            }
+        }
+        if self.deny_hidden && segments.iter().any(|segment| segment.starts_with('.')) {
+            return Err(SafePathError::Hidden);
+        }
+
+        let path = segments.join("/");
+        if let Some(pattern) = self
+            .deny
+            .iter()
+            .find(|pattern| matches_pattern(pattern, &path, &segments))
+        {
+            return Err(SafePathError::DeniedPattern(pattern.clone()));
+        }
+        if is_dir {
+            return Ok(());
+        }
+
+        let extension = relative
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        if let Some(ext) = &extension {
+            if self.deny_extensions.contains(ext) {
+                return Err(SafePathError::DeniedExtension(ext.clone()));
+            }
+        }
+
+        let file_name = segments.last().map_or("", String::as_str);
+        let pattern_allowed = self.allow.is_empty()
+            || self.allow.iter().any(|pattern| {
+                if pattern.contains('/') {
+                    glob_match(pattern, &path)
+                } else {
+                    glob_match(pattern, file_name)
+                }
+            });
+        let extension_allowed = self.allow_extensions.is_empty()
+            || extension.is_some_and(|ext| self.allow_extensions.contains(&ext));
+        if !pattern_allowed || !extension_allowed {
+            return Err(SafePathError::NotAllowed);
+        }
+        Ok(())
+    }
+}
+
+/// The normal components of `relative`.
+fn segments(relative: &Path) -> Vec<String> {
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn matches_pattern(pattern: &str, path: &str, segments: &[String]) -> bool {
+    if pattern.contains('/') {
+        glob_match(pattern, path)
+    } else {
+        segments.iter().any(|segment| glob_match(pattern, segment))
+    }
+}
+
+/// Match `text` against a glob with `*`, `**` and `?`.
+///
+/// A `**` segment matches any number of whole segments, except that a
+/// trailing `/**` needs at least one, so `docs/**` does not match `docs`.
+/// Both levels use the iterative two-pointer wildcard match, which never
+/// backtracks further than the last `*`, so matching is polynomial in the
+/// pattern and path length.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut pattern: Vec<&str> = pattern.split('/').collect();
+    if pattern.len() > 1 && pattern.last() == Some(&"**") {
+        pattern.insert(pattern.len() - 1, "*");
+    }
+    let text: Vec<&str> = text.split('/').collect();
+    wildcard_match(
+        &pattern,
+        &text,
+        |p| *p == "**",
+        |p, t| segment_match(p.as_bytes(), t.as_bytes()),
+    )
+}
+
+/// Match a single path segment, where `*` and `?` cannot cross a `/`.
+fn segment_match(pattern: &[u8], text: &[u8]) -> bool {
+    wildcard_match(pattern, text, |&p| p == b'*', |&p, &t| p == b'?' || p == t)
+}
+
+/// Match `text` against `pattern`, where items for which `is_star` holds
+/// match any run of text items and the others match one item each.
+fn wildcard_match<P, T>(
+    pattern: &[P],
+    text: &[T],
+    is_star: impl Fn(&P) -> bool,
+    matches_one: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last star and of the text it is retried from.
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && is_star(&pattern[p]) {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && matches_one(&pattern[p], &text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last star swallow one more item and retry after it.
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_globs() {
+        assert!(glob_match("*.swp", ".notes.md.swp"));
+        assert!(!glob_match("*.md", "docs/readme.md"));
+        assert!(glob_match("docs/*.md", "docs/readme.md"));
+        assert!(glob_match("docs/**/*.md", "docs/readme.md"));
+        assert!(glob_match("docs/**/*.md", "docs/a/b/readme.md"));
+        assert!(glob_match("docs/**", "docs/a/b"));
+        assert!(glob_match("file?.txt", "file1.txt"));
+        assert!(!glob_match("file?.txt", "file10.txt"));
+        assert!(glob_match("**/*.md", "readme.md"));
+        assert!(glob_match("docs/**/b/*.md", "docs/a/b/c/b/x.md"));
+        assert!(!glob_match("docs/**", "docs"));
+        assert!(!glob_match("docs/*", "docs/a/b"));
+    }
+
+    #[test]
+    fn matches_pathological_globs_quickly() {
+        let text = "a".repeat(100);
+        assert!(!glob_match(&("*a".repeat(50) + "b"), &text));
+        let path = vec!["a"; 50].join("/");
+        assert!(!glob_match(&("**/a/".repeat(25) + "b"), &path));
+    }
+
+    #[test]
+    fn checks_resolved_paths() {
+        let policy = PathPolicy::new()
+            .deny_hidden(true)
+            .deny_pattern("node_modules")
+            .deny_extension("KEY")
+            .max_depth(3);
+        assert!(policy.check(Path::new("docs/readme.md"), false).is_ok());
+        assert!(matches!(
+            policy.check(Path::new("docs/.env"), false),
+            Err(SafePathError::Hidden)
+        ));
+        assert!(matches!(
+            policy.check(Path::new("app/node_modules/x.js"), false),
+            Err(SafePathError::DeniedPattern(p)) if p == "node_modules"
+        ));
+        assert!(matches!(
+            policy.check(Path::new("tls/server.Key"), false),
+            Err(SafePathError::DeniedExtension(e)) if e == "key"
+        ));
+        assert!(matches!(
+            policy.check(Path::new("a/b/c/d"), false),
+            Err(SafePathError::TooDeep { depth: 4, max: 3 })
+        ));
+    }
+
+    #[test]
+    fn prunes_denied_directories() {
+        let policy = PathPolicy::new()
+            .deny_hidden(true)
+            .deny_pattern("node_modules")
+            .deny_pattern("build/*.tmp")
+            .allow_extension("md")
+            .max_depth(3);
+        assert!(policy.check_dir(Path::new("docs/a")).is_ok());
+        assert!(policy.check_dir(Path::new("build/x.tmp")).is_ok());
+        assert!(matches!(
+            policy.check_dir(Path::new(".git")),
+            Err(SafePathError::Hidden)
+        ));
+        assert!(matches!(
+            policy.check_dir(Path::new("app/node_modules")),
+            Err(SafePathError::DeniedPattern(p)) if p == "node_modules"
+        ));
+        assert!(matches!(
+            policy.check_dir(Path::new("a/b/c")),
+            Err(SafePathError::TooDeep { depth: 4, max: 3 })
+        ));
+    }
+
+    #[test]
+    fn allow_lists_restrict_paths() {
+        let policy = PathPolicy::new()
+            .allow_pattern("docs/**")
+            .allow_pattern("*.txt")
+            .allow_extension("md")
+            .allow_extension("txt");
+        assert!(policy.check(Path::new("docs/a/readme.md"), false).is_ok());
+        assert!(policy.check(Path::new("notes.txt"), false).is_ok());
+        assert!(matches!(
+            policy.check(Path::new("docs/image.png"), false),
+            Err(SafePathError::NotAllowed)
+        ));
+        assert!(matches!(
+            policy.check(Path::new("readme.md"), false),
+            Err(SafePathError::NotAllowed)
+        ));
+        assert!(matches!(
+            policy.check(Path::new("notes.txt/secret.key"), false),
+            Err(SafePathError::NotAllowed)
+        ));
+        assert!(policy.check(Path::new("images"), true).is_ok());
+    }
+}
@@ -0,0 +1,154 @@
+//! Request rate limit settings.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::env_file::{env_var, Lookup};
+
+/// Limits applied to one client (a principal or a session).
+///
+/// `None` fields are unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct RateLimit {
+    /// Sustained requests per second.
+    pub requests_per_second: Option<f64>,
+    /// Requests allowed in a burst (default: `requests_per_second`, at least 1).
+    pub burst: Option<u32>,
+    /// Requests forwarded to the MCP service but not yet answered.
+    pub max_in_flight: Option<usize>,
+}
+
+impl RateLimit {
+    /// Read `<prefix>_RPS`, `<prefix>_BURST` and `<prefix>_IN_FLIGHT`.
+    fn from_lookup(prefix: &str, lookup: Lookup<'_>) -> Self {
+        let var = |name: String| lookup(&name);
+
+        Self {
+            requests_per_second: var(format!("{prefix}_RPS"))
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|rps| *rps > 0.0),
+            burst: var(format!("{prefix}_BURST")).and_then(|v| v.parse().ok()),
+            max_in_flight: var(format!("{prefix}_IN_FLIGHT")).and_then(|v| v.parse().ok()),
+        }
+    }
+
+    /// Bucket size for the token bucket, if the rate is limited.
+    pub fn bucket_size(&self) -> Option<f64> {
+        let rps = self.requests_per_second?;
+        let burst = self.burst.map(f64::from).unwrap_or(rps.ceil());
+        Some(burst.max(1.0))
+    }
+
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.requests_per_second.is_none() && self.max_in_flight.is_none()
+    }
+}
+
+/// Formats as e.g. `10 rps, burst 20, 4 in flight`, or `unlimited`.
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(rps) = self.requests_per_second {
+            parts.push(format!("{rps} rps"));
+        }
+        if let (Some(burst), Some(_)) = (self.burst, self.requests_per_second) {
+            parts.push(format!("burst {burst}"));
+        }
+        if let Some(max) = self.max_in_flight {
+            parts.push(format!("{max} in flight"));
+        }
+        if parts.is_empty() {
+            f.write_str("unlimited")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+/// Rate limits for MCP requests posted to the SSE transport.
+///
+/// | Variable | Description |
+/// |----------|-------------|
+/// | `RATE_LIMIT_PRINCIPAL_RPS` | Requests per second per principal |
+/// | `RATE_LIMIT_PRINCIPAL_BURST` | Burst size per principal |
+/// | `RATE_LIMIT_PRINCIPAL_IN_FLIGHT` | Concurrent requests per principal |
+/// | `RATE_LIMIT_SESSION_RPS` | Requests per second per session |
+/// | `RATE_LIMIT_SESSION_BURST` | Burst size per session |
+/// | `RATE_LIMIT_SESSION_IN_FLIGHT` | Concurrent requests per session |
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct RateLimits {
+    /// Shared by all sessions of the same principal.
+    pub per_principal: RateLimit,
+    /// Applied to each session separately.
+    pub per_session: RateLimit,
+}
+
+impl RateLimits {
+    /// Read the limits from the environment; unset variables are unlimited.
+    pub fn from_env() -> Self {
+        Self::from_lookup(&env_var)
+    }
+
+    pub(crate) fn from_lookup(lookup: Lookup<'_>) -> Self {
+        Self {
+            per_principal: RateLimit::from_lookup("RATE_LIMIT_PRINCIPAL", lookup),
+            per_session: RateLimit::from_lookup("RATE_LIMIT_SESSION", lookup),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_defaults_to_rate() {
+        let limit = RateLimit {
+            requests_per_second: Some(2.5),
+            ..Default::default()
+        };
+        assert_eq!(limit.bucket_size(), Some(3.0));
+
+        let limit = RateLimit {
+            requests_per_second: Some(0.2),
+            ..Default::default()
+        };
+        assert_eq!(limit.bucket_size(), Some(1.0));
+
+        let limit = RateLimit {
+            requests_per_second: Some(5.0),
+            burst: Some(20),
+            max_in_flight: None,
+        };
+        assert_eq!(limit.bucket_size(), Some(20.0));
+        assert_eq!(RateLimit::default().bucket_size(), None);
+    }
+
+    #[test]
+    fn formats_limits() {
+        let limit = RateLimit {
+            requests_per_second: Some(10.0),
+            burst: Some(20),
+            max_in_flight: Some(4),
+        };
+        assert_eq!(limit.to_string(), "10 rps, burst 20, 4 in flight");
+        assert_eq!(RateLimit::default().to_string(), "unlimited");
+    }
+
+    #[test]
+    fn reads_limits_from_lookup() {
+        let vars = std::collections::HashMap::from([
+            ("RATE_LIMIT_SESSION_RPS", "10"),
+            ("RATE_LIMIT_SESSION_IN_FLIGHT", "4"),
+            ("RATE_LIMIT_PRINCIPAL_RPS", "-1"),
+        ]);
+        let lookup = |name: &str| vars.get(name).map(|value| value.to_string());
+
+        let limits = RateLimits::from_lookup(&lookup);
+        assert_eq!(limits.per_session.requests_per_second, Some(10.0));
+        assert_eq!(limits.per_session.max_in_flight, Some(4));
+        assert!(limits.per_principal.is_unlimited());
+    }
+}
@@ -0,0 +1,311 @@
+//! Reloading [`BaseConfig`] while the server is running.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::watch;
+
+use super::base::BaseConfig;
+use super::env_file::ConfigFileError;
+use super::secret::{NoSecrets, SecretProvider};
+
+/// Fields that only take effect when the server starts.
+///
+/// The listener is bound and the data directory resolved once, and token
+/// hashes are verified with the pepper the token store was created with.
+const RESTART_FIELDS: &[&str] = &[
+    "host",
+    "port",
+    "data_path",
+    "data_roots",
+    "auth_token_pepper",
+];
+
+/// Watch the config for changes and publish each reloaded version.
+///
+/// The config is read from `path` with [`BaseConfig::from_env_file`], or from
+/// the environment when `path` is `None`. It is reloaded on `SIGHUP` and,
+/// with a `path`, whenever the file changes, which is checked every
+/// `interval`.
+///
+/// Reloaded fields apply live through the receivers:
+/// [`TokenStore::follow_config`](crate::auth::TokenStore::follow_config)
+/// rotates the accepted token, and the SSE transport picks up new rate limits
+/// from `SseServerConfig::reload`. The log filter is applied here when
+/// tracing was initialized by this crate; removing it restores the filter
+/// tracing started with. Changes to fields that need a
+/// restart are logged and not published, so the config seen by receivers is
+/// always the one in effect.
+///
+/// Must be called from within a Tokio runtime. Watching stops when all
+/// receivers are dropped.
+///
+/// Secrets are read from the built-in sources only; use
+/// [`watch_config_with`] if the config was created with
+/// [`BaseConfig::from_env_with`].
+pub fn watch_config(
+    path: Option<PathBuf>,
+    interval: Duration,
+) -> Result<watch::Receiver<Arc<BaseConfig>>, ConfigFileError> {
+    watch_config_with(path, interval, Arc::new(NoSecrets))
+}
+
+/// Like [`watch_config`], asking `secrets` for secret values first on every
+/// load, as [`BaseConfig::from_env_with`] does.
+pub fn watch_config_with(
+    path: Option<PathBuf>,
+    interval: Duration,
+    secrets: Arc<dyn SecretProvider>,
+) -> Result<watch::Receiver<Arc<BaseConfig>>, ConfigFileError> {
+    let mut last = path.as_deref().and_then(modified);
+    let config = load(path.as_deref(), &*secrets)?;
+    let mut hangup = Hangup::new().map_err(ConfigFileError::Io)?;
+    let (tx, rx) = watch::channel(Arc::new(config));
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = tx.closed() => break,
+                _ = hangup.recv() => tracing::info!("received SIGHUP, reloading config"),
+                _ = ticker.tick() => {
+                    let Some(path) = path.as_deref() else { continue };
+                    let current = modified(path);
+                    if current == last {
+                        continue;
+                    }
+                    last = current;
+                }
+            }
+            match load(path.as_deref(), &*secrets) {
+                Ok(next) => apply(&tx, next),
+                Err(e) => tracing::error!(error = %e, "failed to reload config"),
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+fn load(path: Option<&Path>, secrets: &dyn SecretProvider) -> Result<BaseConfig, ConfigFileError> {
+    match path {
+        Some(path) => BaseConfig::from_env_file_with(path, secrets),
+        None => BaseConfig::from_env_with(secrets).map_err(ConfigFileError::from),
+    }
+}
+
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Publish the reloadable changes of `next` and log the rest.
+fn apply(tx: &watch::Sender<Arc<BaseConfig>>, mut next: BaseConfig) {
+    let current = tx.borrow().clone();
+    let changed = changed_fields(&current, &next);
+    let (restart, live): (Vec<_>, Vec<_>) = changed
+        .into_iter()
+        .partition(|field| RESTART_FIELDS.contains(field));
+
+    if !restart.is_empty() {
+        tracing::warn!(fields = ?restart, "config changes need a restart to apply");
+        next.host.clone_from(&current.host);
+        next.port = current.port;
+        next.data_path.clone_from(&current.data_path);
+        next.data_roots.clone_from(&current.data_roots);
+        next.auth_token_pepper
+            .clone_from(&current.auth_token_pepper);
+    }
+    if live.is_empty() {
+        return;
+    }
+
+    #[cfg(feature = "bootstrap")]
+    if live.contains(&"log_filter") {
+        apply_log_filter(next.log_filter.as_deref());
+    }
+    tracing::info!(fields = ?live, "applied config changes");
+    tx.send_replace(Arc::new(next));
+}
+
+#[cfg(feature = "bootstrap")]
+fn apply_log_filter(filter: Option<&str>) {
+    let Some(handle) = crate::bootstrap::log_level_handle() else {
+        return;
+    };
+    // Without a configured filter, go back to the one tracing started with.
+    let result = match filter {
+        Some(filter) => handle.set(filter),
+        None => handle.reset(),
+    };
+    if let Err(e) = result {
+        tracing::error!(error = %e, "failed to apply log filter from config");
+    }
+}
+
+/// Names of the fields that differ. Values are not logged, as some are secret.
+fn changed_fields(old: &BaseConfig, new: &BaseConfig) -> Vec<&'static str> {
+    // Destructured without `..` so a new field cannot be missed here.
+    let BaseConfig {
+        host,
+        port,
+        data_path,
+        data_roots,
+        auth_token,
+        auth_token_hash,
+        auth_token_pepper,
+        log_filter,
+        rate_limits,
+    } = old;
+    let fields = [
+        ("host", *host != new.host),
+        ("port", *port != new.port),
+        ("data_path", *data_path != new.data_path),
+        ("data_roots", *data_roots != new.data_roots),
+        ("auth_token", *auth_token != new.auth_token),
+        ("auth_token_hash", *auth_token_hash != new.auth_token_hash),
+        (
+            "auth_token_pepper",
+            *auth_token_pepper != new.auth_token_pepper,
+        ),
+        ("log_filter", *log_filter != new.log_filter),
+        ("rate_limits", *rate_limits != new.rate_limits),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+}
+
+/// `SIGHUP` notifications; never fires on platforms without signals.
+struct Hangup {
+    #[cfg(unix)]
+    signal: tokio::signal::unix::Signal,
+}
+
+impl Hangup {
+    fn new() -> io::Result<Self> {
+        Ok(Self {
+            #[cfg(unix)]
+            signal: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?,
+        })
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if self.signal.recv().await.is_some() {
+            return;
+        }
+        std::future::pending().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{RateLimits, Secret, SecretError};
+
+    fn config() -> BaseConfig {
+        BaseConfig {
+            host: "127.0.0.1".to_string(),
+            port: 3000,
+            data_path: PathBuf::from("./data"),
+            data_roots: Default::default(),
+            auth_token: Some(Secret::new("old")),
+            auth_token_hash: None,
+            auth_token_pepper: None,
+            log_filter: None,
+            rate_limits: RateLimits::default(),
+        }
+    }
+
+    #[test]
+    fn publishes_only_reloadable_changes() {
+        let (tx, rx) = watch::channel(Arc::new(config()));
+
+        let mut next = config();
+        next.port = 8080;
+        apply(&tx, next);
+        assert!(!rx.has_changed().unwrap());
+
+        let mut next = config();
+        next.port = 8080;
+        next.auth_token = Some(Secret::new("new"));
+        next.rate_limits.per_session.max_in_flight = Some(1);
+        assert_eq!(
+            changed_fields(&config(), &next),
+            ["port", "auth_token", "rate_limits"]
+        );
+        apply(&tx, next);
+        let current = rx.borrow();
+        assert_eq!(current.port, 3000);
+        assert_eq!(current.auth_token, Some(Secret::new("new")));
+        assert_eq!(current.rate_limits.per_session.max_in_flight, Some(1));
+    }
+
+    #[test]
+    fn publishes_removed_log_filter() {
+        let mut old = config();
+        old.log_filter = Some("debug".to_string());
+        let (tx, rx) = watch::channel(Arc::new(old.clone()));
+
+        assert_eq!(changed_fields(&old, &config()), ["log_filter"]);
+        apply(&tx, config());
+        assert_eq!(rx.borrow().log_filter, None);
+    }
+
+    #[tokio::test]
+    async fn reloads_when_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.env");
+        std::fs::write(&path, "AUTH_TOKEN=first\n").unwrap();
+
+        let mut rx = watch_config(Some(path.clone()), Duration::from_millis(10)).unwrap();
+        assert_eq!(
+            rx.borrow_and_update().auth_token,
+            Some(Secret::new("first"))
+        );
+
+        std::fs::write(&path, "AUTH_TOKEN=second-token\n").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rx.borrow().auth_token, Some(Secret::new("second-token")));
+    }
+
+    #[tokio::test]
+    async fn keeps_provided_secrets_on_reload() {
+        struct Vault;
+
+        impl SecretProvider for Vault {
+            fn secret(&self, name: &str) -> Result<Option<Secret>, SecretError> {
+                Ok((name == "AUTH_TOKEN").then(|| Secret::new("from-vault")))
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.env");
+        std::fs::write(&path, "RUST_LOG=info\n").unwrap();
+
+        let mut rx = watch_config_with(
+            Some(path.clone()),
+            Duration::from_millis(10),
+            Arc::new(Vault),
+        )
+        .unwrap();
+        std::fs::write(&path, "RUST_LOG=debug\n").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), rx.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        let config = rx.borrow();
+        assert_eq!(config.log_filter.as_deref(), Some("debug"));
+        assert_eq!(config.auth_token, Some(Secret::new("from-vault")));
+    }
+}
@@ -0,0 +1,627 @@
+//! File access confined to a directory handle.
+//!
+//! [`safe_resolve`](super::safe_resolve) checks a path and returns it, but the
+//! file is opened later by path: a symlink swapped in between the check and
+//! the open can still lead outside the base directory. [`SafeDir`] instead
+//! keeps the base directory open and opens files relative to it, so every
+//! open is checked by the kernel at the time it happens.
+
+use std::{
+    ffi::{CStr, CString, OsStr, OsString},
+    fs::{File, Metadata},
+    io,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
+        unix::{ffi::OsStrExt, fs::OpenOptionsExt},
+    },
+    path::{Component, Path, PathBuf},
+};
+
+use super::safe_path::SafePathError;
+
+/// An open base directory that files are opened and created beneath.
+///
+/// On Linux 5.6+ paths are resolved with
+/// `openat2(RESOLVE_BENEATH | RESOLVE_NO_MAGICLINKS)`, which follows symlinks
+/// only while they stay inside the directory. Elsewhere, or where `openat2`
+/// is unavailable, paths are walked one component at a time with
+/// `O_NOFOLLOW`, and symlinks are refused altogether.
+///
+/// User paths must be relative and must not contain `..`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::io::Write;
+/// use mcp_core::config::SafeDir;
+///
+/// let data = SafeDir::open("/srv/data").unwrap();
+/// let mut file = data.create_file("exports/report.json").unwrap();
+/// file.write_all(b"{}").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SafeDir {
+    dir: File,
+    path: PathBuf,
+}
+
+const FILE_MODE: libc::mode_t = 0o666;
+const DIR_MODE: libc::mode_t = 0o777;
+
+impl SafeDir {
+    /// Open the directory at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SafePathError> {
+        let path = path.as_ref();
+        let dir = File::options()
+            .read(true)
+            .custom_flags(libc::O_DIRECTORY)
+            .open(path)
+            .map_err(SafePathError::InvalidBase)?;
+        Ok(Self {
+            dir,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// The path the directory was opened at, for display only.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Open an existing regular file for reading.
+    ///
+    /// Directories, FIFOs, sockets and devices are refused with
+    /// [`SafePathError::NotFound`]; the file is opened with `O_NONBLOCK` so a
+    /// FIFO without a writer cannot block the open.
+    pub fn open_file(&self, user_path: &str) -> Result<File, SafePathError> {
+        let file = File::from(self.open_at(user_path, libc::O_RDONLY | libc::O_NONBLOCK)?);
+        regular_file(file)
+    }
+
+    /// Create or truncate a file for writing. The parent must exist; see
+    /// [`create_dir_all`](Self::create_dir_all).
+    ///
+    /// Like [`open_file`](Self::open_file), anything but a regular file is
+    /// refused, and it is only truncated once that has been checked.
+    pub fn create_file(&self, user_path: &str) -> Result<File, SafePathError> {
+        let flags = libc::O_WRONLY | libc::O_CREAT | libc::O_NONBLOCK;
+        let file = regular_file(File::from(self.open_at(user_path, flags)?))?;
+        file.set_len(0).map_err(SafePathError::NotFound)?;
+        Ok(file)
+    }
+
+    /// Open a subdirectory as a [`SafeDir`] of its own.
+    pub fn open_dir(&self, user_path: &str) -> Result<SafeDir, SafePathError> {
+        let dir = self.open_at(user_path, libc::O_RDONLY | libc::O_DIRECTORY)?;
+        Ok(Self {
+            dir: File::from(dir),
+            path: self.path.join(user_path),
+        })
+    }
+
+    /// Create a directory and its missing parents.
+    ///
+    /// Each component is created and then opened with `O_NOFOLLOW`, so a
+    /// symlink in the way is refused instead of followed.
+    pub fn create_dir_all(&self, user_path: &str) -> Result<(), SafePathError> {
+        let names = split_user_path(user_path)?;
+        let mut current: Option<OwnedFd> = None;
+        for name in &names {
+            let parent = current.as_ref().map_or(self.dir.as_fd(), |fd| fd.as_fd());
+            match mkdirat(parent, name, DIR_MODE) {
+                Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(map_err(e)),
+                _ => {}
+            }
+            current = Some(open_dir_nofollow(parent, name).map_err(map_err)?);
+        }
+        Ok(())
+    }
+
+    /// Metadata of a file or directory, following symlinks.
+    ///
+    /// On Linux the entry is opened with `O_PATH`, which needs no read
+    /// permission and never opens a device or FIFO.
+    pub fn metadata(&self, user_path: &str) -> Result<Metadata, SafePathError> {
+        #[cfg(target_os = "linux")]
+        let flags = libc::O_PATH;
+        #[cfg(not(target_os = "linux"))]
+        let flags = libc::O_RDONLY | libc::O_NONBLOCK;
+        let file = File::from(self.open_at(user_path, flags)?);
+        let metadata = file.metadata().map_err(SafePathError::NotFound)?;
+        // `O_PATH | O_NOFOLLOW` opens a final symlink instead of failing.
+        if metadata.file_type().is_symlink() {
+            return Err(SafePathError::PathTraversal);
+        }
+        Ok(metadata)
+    }
+
+    /// The entries of a directory, without `.` and `..`, in no particular
+    /// order.
+    pub fn read_dir(&self, user_path: &str) -> Result<Vec<SafeDirEntry>, SafePathError> {
+        let dir = self.open_at(user_path, libc::O_RDONLY | libc::O_DIRECTORY)?;
+        read_dir(dir).map_err(SafePathError::NotFound)
+    }
+
+    /// Remove a file, or a symlink without following it.
+    pub fn remove_file(&self, user_path: &str) -> Result<(), SafePathError> {
+        let (parent, name) = self.parent_at(user_path)?;
+        unlinkat(parent.as_fd(), &name, 0).map_err(map_err)
+    }
+
+    /// Remove an empty directory.
+    pub fn remove_dir(&self, user_path: &str) -> Result<(), SafePathError> {
+        let (parent, name) = self.parent_at(user_path)?;
+        unlinkat(parent.as_fd(), &name, libc::AT_REMOVEDIR).map_err(map_err)
+    }
+
+    /// Rename a file or directory, replacing `to` if it is a file.
+    pub fn rename(&self, from: &str, to: &str) -> Result<(), SafePathError> {
+        let (from_parent, from_name) = self.parent_at(from)?;
+        let (to_parent, to_name) = self.parent_at(to)?;
+        renameat(from_parent.as_fd(), &from_name, to_parent.as_fd(), &to_name).map_err(map_err)
+    }
+
+    /// Open `user_path` beneath the directory with the given `open(2)` flags.
+    pub(crate) fn open_at(
+        &self,
+        user_path: &str,
+        flags: libc::c_int,
+    ) -> Result<OwnedFd, SafePathError> {
+        self.open_names(&split_user_path(user_path)?, flags)
+    }
+
+    /// The parent directory of `user_path` and its final name, which is not
+    /// resolved, so operations on it act on a symlink rather than its target.
+    pub(crate) fn parent_at(&self, user_path: &str) -> Result<(OwnedFd, CString), SafePathError> {
+        let mut names = split_user_path(user_path)?;
+        let name = names.pop().ok_or(SafePathError::PathTraversal)?;
+        let parent = self.open_names(&names, libc::O_RDONLY | libc::O_DIRECTORY)?;
+        Ok((parent, name))
+    }
+
+    fn open_names(&self, names: &[CString], flags: libc::c_int) -> Result<OwnedFd, SafePathError> {
+        if names.is_empty() {
+            return openat(self.dir.as_fd(), c".", flags, FILE_MODE).map_err(map_err);
+        }
+        #[cfg(target_os = "linux")]
+        match openat2(&self.dir, names, flags, FILE_MODE) {
+            Err(e) if matches!(e.raw_os_error(), Some(libc::ENOSYS | libc::EPERM)) => {}
+            result => return result.map_err(map_err),
+        }
+        open_walking(&self.dir, names, flags, FILE_MODE).map_err(map_err)
+    }
+}
+
+/// Return `file`, opened with `O_NONBLOCK`, in blocking mode if it is a
+/// regular file.
+fn regular_file(file: File) -> Result<File, SafePathError> {
+    let metadata = file.metadata().map_err(SafePathError::NotFound)?;
+    if !metadata.is_file() {
+        return Err(SafePathError::NotFound(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a regular file",
+        )));
+    }
+    clear_nonblocking(file.as_fd()).map_err(SafePathError::NotFound)?;
+    Ok(file)
+}
+
+/// An entry of a directory listed with [`SafeDir::read_dir`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafeDirEntry {
+    /// File name within the directory.
+    pub name: OsString,
+    /// Type of the entry itself; symlinks are not followed.
+    pub kind: EntryKind,
+    /// Size in bytes.
+    pub len: u64,
+}
+
+/// Type of a [`SafeDirEntry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    /// A regular file.
+    File,
+    /// A directory.
+    Dir,
+    /// A symbolic link.
+    Symlink,
+    /// A socket, FIFO or device.
+    Other,
+}
+
+impl EntryKind {
+    fn from_mode(mode: libc::mode_t) -> Self {
+        match mode & libc::S_IFMT {
+            libc::S_IFREG => Self::File,
+            libc::S_IFDIR => Self::Dir,
+            libc::S_IFLNK => Self::Symlink,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Split a user path into names, rejecting anything but plain components.
+fn split_user_path(user_path: &str) -> Result<Vec<CString>, SafePathError> {
+    if user_path.starts_with('/') || user_path.starts_with('\\') {
+        return Err(SafePathError::PathTraversal);
+    }
+    Path::new(user_path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .map(|component| match component {
+            Component::Normal(name) => cstring(name),
+            _ => Err(SafePathError::PathTraversal),
+        })
+        .collect()
+}
+
+fn cstring(name: &OsStr) -> Result<CString, SafePathError> {
+    CString::new(name.as_bytes()).map_err(|_| SafePathError::PathTraversal)
+}
+
+/// Escapes show up as `EXDEV` from `openat2` and `ELOOP` from `O_NOFOLLOW`.
+fn map_err(e: io::Error) -> SafePathError {
+    match e.raw_os_error() {
+        Some(libc::EXDEV | libc::ELOOP) => SafePathError::PathTraversal,
+        _ => SafePathError::NotFound(e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn openat2(
+    dir: &File,
+    names: &[CString],
+    flags: libc::c_int,
+    mode: libc::mode_t,
+) -> io::Result<OwnedFd> {
+    let path = names
+        .iter()
+        .map(|name| name.as_bytes())
+        .collect::<Vec<_>>()
+        .join(&b'/');
+    let path = CString::new(path).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    // SAFETY: `open_how` is a plain C struct for which all zeroes is valid.
+    let mut how: libc::open_how = unsafe { std::mem::zeroed() };
+    how.flags = (flags | libc::O_CLOEXEC) as u64;
+    if flags & libc::O_CREAT != 0 {
+        how.mode = u64::from(mode);
+    }
+    how.resolve = libc::RESOLVE_BENEATH | libc::RESOLVE_NO_MAGICLINKS;
+    // SAFETY: the path is NUL-terminated and `how` outlives the call, which
+    // is passed its exact size.
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_openat2,
+            dir.as_raw_fd(),
+            path.as_ptr(),
+            &how as *const libc::open_how,
+            std::mem::size_of::<libc::open_how>(),
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: the syscall returned a new descriptor that nothing else owns.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) })
+}
+
+/// Open one component at a time without following symlinks.
+fn open_walking(
+    dir: &File,
+    names: &[CString],
+    flags: libc::c_int,
+    mode: libc::mode_t,
+) -> io::Result<OwnedFd> {
+    let Some((last, parents)) = names.split_last() else {
+        return Err(io::Error::from(io::ErrorKind::InvalidInput));
+    };
+    let mut current: Option<OwnedFd> = None;
+    for name in parents {
+        let parent = current.as_ref().map_or(dir.as_fd(), |fd| fd.as_fd());
+        current = Some(open_dir_nofollow(parent, name)?);
+    }
+    let parent = current.as_ref().map_or(dir.as_fd(), |fd| fd.as_fd());
+    openat(parent, last, flags | libc::O_NOFOLLOW, mode)
+}
+
+/// Open a directory, failing with `ELOOP` if `name` is a symlink.
+fn open_dir_nofollow(dir: BorrowedFd<'_>, name: &CStr) -> io::Result<OwnedFd> {
+    let flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW;
+    openat(dir, name, flags, 0).map_err(|e| {
+        // With `O_DIRECTORY`, Linux reports a symlink as `ENOTDIR`.
+        if e.raw_os_error() == Some(libc::ENOTDIR) && is_symlink_at(dir, name) {
+            io::Error::from_raw_os_error(libc::ELOOP)
+        } else {
+            e
+        }
+    })
+}
+
+fn is_symlink_at(dir: BorrowedFd<'_>, name: &CStr) -> bool {
+    lstatat(dir.as_raw_fd(), name)
+        .is_ok_and(|stat| EntryKind::from_mode(stat.st_mode) == EntryKind::Symlink)
+}
+
+/// `stat` of `name` in `dir`, without following a symlink.
+fn lstatat(dir: RawFd, name: &CStr) -> io::Result<libc::stat> {
+    // SAFETY: all-zero is a valid `stat`, which `fstatat` fills in.
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    // SAFETY: `name` is NUL-terminated, `dir` is open and `stat` is writable.
+    let result = unsafe { libc::fstatat(dir, name.as_ptr(), &mut stat, libc::AT_SYMLINK_NOFOLLOW) };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(stat)
+}
+
+fn read_dir(dir: OwnedFd) -> io::Result<Vec<SafeDirEntry>> {
+    // SAFETY: `fdopendir` takes ownership of the descriptor on success.
+    let stream = unsafe { libc::fdopendir(dir.as_raw_fd()) };
+    if stream.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    let dir = dir.into_raw_fd();
+    let mut entries = Vec::new();
+    let mut result = Ok(());
+    loop {
+        // SAFETY: `stream` is an open directory stream.
+        let entry = unsafe { libc::readdir(stream) };
+        if entry.is_null() {
+            break;
+        }
+        // SAFETY: `readdir` returned a valid entry with a NUL-terminated name,
+        // which stays valid until the next call on `stream`.
+        let name = unsafe { CStr::from_ptr((*entry).d_name.as_ptr()) };
+        if name == c"." || name == c".." {
+            continue;
+        }
+        match lstatat(dir, name) {
+            Ok(stat) => entries.push(SafeDirEntry {
+                name: OsStr::from_bytes(name.to_bytes()).to_os_string(),
+                kind: EntryKind::from_mode(stat.st_mode),
+                len: stat.st_size as u64,
+            }),
+            // Removed since it was listed.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    // SAFETY: `stream` is open and owns `dir`, which is closed with it.
+    unsafe { libc::closedir(stream) };
+    result.map(|()| entries)
+}
+
+fn unlinkat(dir: BorrowedFd<'_>, name: &CStr, flags: libc::c_int) -> io::Result<()> {
+    // SAFETY: `name` is NUL-terminated and `dir` is an open descriptor.
+    if unsafe { libc::unlinkat(dir.as_raw_fd(), name.as_ptr(), flags) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn renameat(
+    from_dir: BorrowedFd<'_>,
+    from: &CStr,
+    to_dir: BorrowedFd<'_>,
+    to: &CStr,
+) -> io::Result<()> {
+    // SAFETY: both names are NUL-terminated and both descriptors are open.
+    let result = unsafe {
+        libc::renameat(
+            from_dir.as_raw_fd(),
+            from.as_ptr(),
+            to_dir.as_raw_fd(),
+            to.as_ptr(),
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn openat(
+    dir: BorrowedFd<'_>,
+    name: &CStr,
+    flags: libc::c_int,
+    mode: libc::mode_t,
+) -> io::Result<OwnedFd> {
+    // SAFETY: `name` is NUL-terminated and `dir` is an open descriptor.
+    let fd = unsafe {
+        libc::openat(
+            dir.as_raw_fd(),
+            name.as_ptr(),
+            flags | libc::O_CLOEXEC,
+            libc::c_uint::from(mode),
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `openat` returned a new descriptor that nothing else owns.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Switch a descriptor opened with `O_NONBLOCK` back to blocking reads.
+fn clear_nonblocking(fd: BorrowedFd<'_>) -> io::Result<()> {
+    // SAFETY: `fd` is an open descriptor.
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    if flags < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: as above; only the file status flags are changed.
+    if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn mkdirat(dir: BorrowedFd<'_>, name: &CStr, mode: libc::mode_t) -> io::Result<()> {
+    // SAFETY: `name` is NUL-terminated and `dir` is an open descriptor.
+    if unsafe { libc::mkdirat(dir.as_raw_fd(), name.as_ptr(), mode) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::os::unix::fs::{self as unix_fs, FileTypeExt, OpenOptionsExt};
+
+    fn setup_dir() -> (tempfile::TempDir, SafeDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("data");
+        fs::create_dir_all(base.join("sub")).unwrap();
+        fs::write(base.join("sub/file.txt"), "hello").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let safe = SafeDir::open(&base).unwrap();
+        (dir, safe)
+    }
+
+    #[test]
+    fn opens_and_creates_files() {
+        let (dir, safe) = setup_dir();
+        let mut contents = String::new();
+        safe.open_file("./sub/file.txt")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "hello");
+
+        safe.create_dir_all("new/nested").unwrap();
+        safe.create_file("new/nested/out.txt")
+            .unwrap()
+            .write_all(b"written")
+            .unwrap();
+        let written = fs::read_to_string(dir.path().join("data/new/nested/out.txt")).unwrap();
+        assert_eq!(written, "written");
+
+        assert!(matches!(
+            safe.open_file("missing.txt"),
+            Err(SafePathError::NotFound(_))
+        ));
+        assert!(safe.open_dir("sub").unwrap().open_file("file.txt").is_ok());
+    }
+
+    #[test]
+    fn rejects_traversal() {
+        let (_dir, safe) = setup_dir();
+        for path in [
+            "../secret.txt",
+            "sub/../../secret.txt",
+            "/etc/passwd",
+            "a\0b",
+        ] {
+            assert!(
+                matches!(safe.open_file(path), Err(SafePathError::PathTraversal)),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_symlinks_outside_base() {
+        let (dir, safe) = setup_dir();
+        let base = dir.path().join("data");
+        unix_fs::symlink(dir.path().join("secret.txt"), base.join("file_link")).unwrap();
+        unix_fs::symlink(dir.path(), base.join("dir_link")).unwrap();
+
+        for path in ["file_link", "dir_link/secret.txt"] {
+            assert!(
+                matches!(safe.open_file(path), Err(SafePathError::PathTraversal)),
+                "{path}"
+            );
+        }
+        assert!(matches!(
+            safe.create_file("dir_link/new.txt"),
+            Err(SafePathError::PathTraversal)
+        ));
+        assert!(matches!(
+            safe.create_dir_all("dir_link/new"),
+            Err(SafePathError::PathTraversal)
+        ));
+        assert!(!dir.path().join("new.txt").exists());
+        assert!(!dir.path().join("new").exists());
+    }
+
+    #[test]
+    fn opens_regular_files_only() {
+        let (dir, safe) = setup_dir();
+        let base = dir.path().join("data");
+        let fifo = CString::new(base.join("pipe").as_os_str().as_bytes()).unwrap();
+        // SAFETY: `fifo` is a NUL-terminated path.
+        assert_eq!(unsafe { libc::mkfifo(fifo.as_ptr(), 0o600) }, 0);
+        unix_fs::symlink("sub/file.txt", base.join("link")).unwrap();
+
+        // Neither blocks waiting for a writer.
+        assert!(matches!(
+            safe.open_file("pipe"),
+            Err(SafePathError::NotFound(_))
+        ));
+        assert!(matches!(
+            safe.open_file("sub"),
+            Err(SafePathError::NotFound(_))
+        ));
+        let file = safe.open_file("sub/file.txt").unwrap();
+        // SAFETY: `file` is open.
+        let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+        assert_eq!(flags & libc::O_NONBLOCK, 0);
+
+        assert!(safe.metadata("pipe").unwrap().file_type().is_fifo());
+        assert!(safe.metadata("sub").unwrap().is_dir());
+        assert_eq!(safe.metadata("sub/file.txt").unwrap().len(), 5);
+        assert!(safe.metadata("link").is_ok());
+    }
+
+    #[test]
+    fn creates_regular_files_only() {
+        let (dir, safe) = setup_dir();
+        let base = dir.path().join("data");
+        let fifo = CString::new(base.join("pipe").as_os_str().as_bytes()).unwrap();
+        // SAFETY: `fifo` is a NUL-terminated path.
+        assert_eq!(unsafe { libc::mkfifo(fifo.as_ptr(), 0o600) }, 0);
+        // Keep a reader open, so opening the FIFO for writing succeeds.
+        let _reader = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(base.join("pipe"))
+            .unwrap();
+
+        // Does not block waiting for a reader.
+        assert!(safe.create_file("pipe").is_err());
+        assert!(safe.create_file("sub").is_err());
+
+        let file = safe.create_file("file.txt").unwrap();
+        // SAFETY: `file` is open.
+        let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+        assert_eq!(flags & libc::O_NONBLOCK, 0);
+        assert_eq!(fs::read_to_string(base.join("file.txt")).unwrap(), "");
+    }
+
+    #[test]
+    fn walking_refuses_all_symlinks() {
+        let (dir, safe) = setup_dir();
+        let base = dir.path().join("data");
+        unix_fs::symlink(base.join("sub"), base.join("inside")).unwrap();
+        let names = split_user_path("inside/file.txt").unwrap();
+
+        let err = open_walking(&safe.dir, &names, libc::O_RDONLY, 0).unwrap_err();
+        assert!(matches!(map_err(err), SafePathError::PathTraversal));
+        let names = split_user_path("sub/file.txt").unwrap();
+        assert!(open_walking(&safe.dir, &names, libc::O_RDONLY, 0).is_ok());
+
+        #[cfg(target_os = "linux")]
+        {
+            let names = split_user_path("inside").unwrap();
+            let fd = open_walking(&safe.dir, &names, libc::O_PATH, 0).unwrap();
+            assert!(File::from(fd).metadata().unwrap().file_type().is_symlink());
+        }
+    }
+}
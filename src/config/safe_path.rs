@@ -5,11 +5,15 @@
 //! via `..` components, absolute paths, or symlinks pointing outside.
 
 use std::fmt;
+use std::fs;
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+pub use super::path_policy::PathPolicy;
 
 /// Error returned when path resolution fails.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum SafePathError {
     /// The base directory does not exist or is inaccessible.
     InvalidBase(io::Error),
@@ -17,6 +21,36 @@ pub enum SafePathError {
     NotFound(io::Error),
     /// The path would escape the base directory (traversal or external symlink).
     PathTraversal,
+    /// A path segment is hidden (starts with `.`) and the policy denies those.
+    Hidden,
+    /// The path passes through a symlink and the policy denies those.
+    Symlink,
+    /// The path matches the given deny pattern.
+    DeniedPattern(String),
+    /// The file has the given denied extension.
+    DeniedExtension(String),
+    /// The path matches none of the policy's allow patterns or extensions.
+    NotAllowed,
+    /// The path has more segments than the policy allows.
+    #[non_exhaustive]
+    TooDeep {
+        /// Number of segments.
+        depth: usize,
+        /// Maximum number of segments.
+        max: usize,
+    },
+    /// The path is longer than the policy allows.
+    #[non_exhaustive]
+    TooLong {
+        /// Length in bytes.
+        len: usize,
+        /// Maximum length in bytes.
+        max: usize,
+    },
+    /// No data root has the given name.
+    UnknownRoot(String),
+    /// The named data root is read-only.
+    ReadOnly(String),
 }
 
 impl fmt::Display for SafePathError {
@@ -25,6 +59,17 @@ impl fmt::Display for SafePathError {
             Self::InvalidBase(e) => write!(f, "invalid base directory: {e}"),
             Self::NotFound(e) => write!(f, "path not found: {e}"),
             Self::PathTraversal => write!(f, "path traversal denied"),
+            Self::Hidden => write!(f, "hidden path denied"),
+            Self::Symlink => write!(f, "symlink denied"),
+            Self::DeniedPattern(pattern) => write!(f, "path matches denied pattern `{pattern}`"),
+            Self::DeniedExtension(ext) => write!(f, "file extension `{ext}` denied"),
+            Self::NotAllowed => write!(f, "path not allowed"),
+            Self::TooDeep { depth, max } => {
+                write!(f, "path has {depth} segments, more than {max}")
+            }
+            Self::TooLong { len, max } => write!(f, "path is {len} bytes, longer than {max}"),
+            Self::UnknownRoot(name) => write!(f, "unknown data root `{name}`"),
+            Self::ReadOnly(name) => write!(f, "data root `{name}` is read-only"),
         }
     }
 }
@@ -33,7 +78,7 @@ impl std::error::Error for SafePathError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::InvalidBase(e) | Self::NotFound(e) => Some(e),
-            Self::PathTraversal => None,
+            _ => None,
         }
     }
 }
@@ -76,10 +121,152 @@ pub fn safe_resolve(base: &Path, user_path: &str) -> Result<PathBuf, SafePathErr
     Ok(canonical)
 }
 
+/// Safely resolve a user-provided path to a file that may not exist yet.
+///
+/// Like [`safe_resolve`], but for paths about to be written: the deepest
+/// existing ancestor is canonicalized and checked against `base`, and the
+/// remaining components must be plain names (no `..`). With
+/// `create_parents`, missing parent directories are created; otherwise the
+/// parent must exist. If the whole path exists, its canonical path is returned
+/// as with [`safe_resolve`].
+///
+/// Existing symlinks are followed and must stay within `base`; dangling ones
+/// are rejected, since writing through them would create their target.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::path::Path;
+/// use mcp_core::config::safe_path::safe_resolve_for_create;
+///
+/// let base = Path::new("/srv/data");
+/// let path = safe_resolve_for_create(base, "exports/2024/report.json", true).unwrap();
+/// std::fs::write(&path, "{}").unwrap();
+/// ```
+pub fn safe_resolve_for_create(
+    base: &Path,
+    user_path: &str,
+    create_parents: bool,
+) -> Result<PathBuf, SafePathError> {
+    if user_path.starts_with('/') || user_path.starts_with('\\') || user_path.contains('\0') {
+        return Err(SafePathError::PathTraversal);
+    }
+
+    let canonical_base = base.canonicalize().map_err(SafePathError::InvalidBase)?;
+
+    // Resolve the existing prefix component by component, so `..` and
+    // symlinks are resolved on disk and checked at every step.
+    let mut resolved = canonical_base.clone();
+    let mut components = Path::new(user_path).components().peekable();
+    while let Some(component) = components.peek() {
+        let candidate = match component {
+            Component::CurDir => {
+                components.next();
+                continue;
+            }
+            Component::ParentDir | Component::Normal(_) => resolved.join(component),
+            Component::RootDir | Component::Prefix(_) => return Err(SafePathError::PathTraversal),
+        };
+        if candidate.symlink_metadata().is_err() {
+            break;
+        }
+        // Exists but cannot be canonicalized: a dangling symlink.
+        resolved = candidate
+            .canonicalize()
+            .map_err(|_| SafePathError::PathTraversal)?;
+        if !resolved.starts_with(&canonical_base) {
+            return Err(SafePathError::PathTraversal);
+        }
+        components.next();
+    }
+
+    // The rest does not exist yet and can only be validated lexically.
+    let mut missing = Vec::new();
+    for component in components {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(name) => missing.push(name),
+            _ => return Err(SafePathError::PathTraversal),
+        }
+    }
+    let Some((file_name, parents)) = missing.split_last() else {
+        return Ok(resolved);
+    };
+
+    let mut parent = resolved;
+    if !parents.is_empty() {
+        if !create_parents {
+            return Err(SafePathError::NotFound(io::Error::new(
+                io::ErrorKind::NotFound,
+                "parent directory does not exist",
+            )));
+        }
+        parent.extend(parents);
+        fs::create_dir_all(&parent).map_err(SafePathError::NotFound)?;
+        // Check again in case a symlink was swapped in while creating.
+        parent = parent.canonicalize().map_err(SafePathError::NotFound)?;
+        if !parent.starts_with(&canonical_base) {
+            return Err(SafePathError::PathTraversal);
+        }
+    }
+
+    Ok(parent.join(file_name))
+}
+
+/// Safely resolve a user-provided path within a base directory, subject to
+/// `policy`.
+///
+/// Like [`safe_resolve`], and additionally rejects paths the policy denies,
+/// with an error variant naming the rule.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::path::Path;
+/// use mcp_core::config::safe_path::{safe_resolve_with, PathPolicy, SafePathError};
+///
+/// let policy = PathPolicy::new().deny_hidden(true);
+/// let result = safe_resolve_with(Path::new("/srv/data"), ".git/config", &policy);
+/// assert!(matches!(result, Err(SafePathError::Hidden)));
+/// ```
+pub fn safe_resolve_with(
+    base: &Path,
+    user_path: &str,
+    policy: &PathPolicy,
+) -> Result<PathBuf, SafePathError> {
+    policy.check_user_path(user_path)?;
+    if policy.denies_symlinks() {
+        check_no_symlinks(base, user_path)?;
+    }
+    let canonical = safe_resolve(base, user_path)?;
+    let canonical_base = base.canonicalize().map_err(SafePathError::InvalidBase)?;
+    let relative = canonical
+        .strip_prefix(&canonical_base)
+        .map_err(|_| SafePathError::PathTraversal)?;
+    policy.check(relative, canonical.is_dir())?;
+    Ok(canonical)
+}
+
+/// Reject `user_path` if any prefix of it below `base` is a symlink.
+fn check_no_symlinks(base: &Path, user_path: &str) -> Result<(), SafePathError> {
+    let mut current = base.to_path_buf();
+    for component in Path::new(user_path).components() {
+        current.push(component);
+        if let Component::Normal(_) = component {
+            let is_symlink = current
+                .symlink_metadata()
+                .is_ok_and(|metadata| metadata.file_type().is_symlink());
+            if is_symlink {
+                return Err(SafePathError::Symlink);
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
     use std::os::unix::fs as unix_fs;
 
     fn setup_dir() -> tempfile::TempDir {
@@ -181,4 +368,102 @@ mod tests {
         let result = safe_resolve(Path::new("/does/not/exist"), "file.txt");
         assert!(matches!(result, Err(SafePathError::InvalidBase(_))));
     }
+
+    #[test]
+    fn resolves_new_file_in_existing_directory() {
+        let dir = setup_dir();
+        let base = dir.path().join("data");
+        let result = safe_resolve_for_create(&base, "sub/./new.txt", false).unwrap();
+        assert_eq!(result, base.canonicalize().unwrap().join("sub/new.txt"));
+
+        let result = safe_resolve_for_create(&base, "sub/../new.txt", false).unwrap();
+        assert_eq!(result, base.canonicalize().unwrap().join("new.txt"));
+
+        let existing = safe_resolve_for_create(&base, "file.txt", false).unwrap();
+        assert_eq!(existing, safe_resolve(&base, "file.txt").unwrap());
+    }
+
+    #[test]
+    fn creates_missing_parents_on_request() {
+        let dir = setup_dir();
+        let base = dir.path().join("data");
+        let result = safe_resolve_for_create(&base, "a/b/new.txt", false);
+        assert!(matches!(result, Err(SafePathError::NotFound(_))));
+        assert!(!base.join("a").exists());
+
+        let result = safe_resolve_for_create(&base, "a/b/new.txt", true).unwrap();
+        assert_eq!(result, base.canonicalize().unwrap().join("a/b/new.txt"));
+        assert!(base.join("a/b").is_dir());
+    }
+
+    #[test]
+    fn rejects_traversal_for_new_paths() {
+        let dir = setup_dir();
+        let base = dir.path().join("data");
+        for path in [
+            "../new.txt",
+            "sub/../../new.txt",
+            "new/../../x",
+            "/tmp/new.txt",
+        ] {
+            let result = safe_resolve_for_create(&base, path, true);
+            assert!(
+                matches!(result, Err(SafePathError::PathTraversal)),
+                "{path}"
+            );
+        }
+        assert!(!base.join("new").exists());
+    }
+
+    #[test]
+    fn rejects_symlinks_outside_base_for_new_paths() {
+        let dir = setup_dir();
+        let base = dir.path().join("data");
+        let outside = dir.path().join("outside");
+        fs::create_dir(&outside).unwrap();
+        unix_fs::symlink(&outside, base.join("escape")).unwrap();
+        unix_fs::symlink(outside.join("missing.txt"), base.join("dangling")).unwrap();
+
+        for path in ["escape/new.txt", "escape/a/new.txt", "dangling"] {
+            let result = safe_resolve_for_create(&base, path, true);
+            assert!(
+                matches!(result, Err(SafePathError::PathTraversal)),
+                "{path}"
+            );
+        }
+        assert!(!outside.join("a").exists());
+    }
+
+    #[test]
+    fn applies_path_policy() {
+        let dir = setup_dir();
+        let base = dir.path().join("data");
+        fs::create_dir(base.join(".git")).unwrap();
+        fs::write(base.join(".git/config"), "[core]").unwrap();
+        unix_fs::symlink(base.join(".git"), base.join("repo")).unwrap();
+        unix_fs::symlink(base.join("file.txt"), base.join("link.txt")).unwrap();
+
+        let policy = PathPolicy::new().deny_hidden(true).max_len(20);
+        assert!(safe_resolve_with(&base, "sub/nested/deep.txt", &policy).is_ok());
+        for path in [".git/config", "repo/config", "sub/../.git/config"] {
+            assert!(
+                matches!(
+                    safe_resolve_with(&base, path, &policy),
+                    Err(SafePathError::Hidden)
+                ),
+                "{path}"
+            );
+        }
+        assert!(matches!(
+            safe_resolve_with(&base, "sub/nested/../nested/deep.txt", &policy),
+            Err(SafePathError::TooLong { max: 20, .. })
+        ));
+
+        let policy = PathPolicy::new().deny_symlinks(true);
+        assert!(safe_resolve_with(&base, "file.txt", &policy).is_ok());
+        assert!(matches!(
+            safe_resolve_with(&base, "link.txt", &policy),
+            Err(SafePathError::Symlink)
+        ));
+    }
 }
@@ -0,0 +1,213 @@
+//! Secret values and the sources they are read from.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, Serializer};
+use zeroize::Zeroizing;
+
+use super::env_file::Lookup;
+
+/// A secret string, wiped from memory when dropped.
+///
+/// `Debug`, `Display` and serialization print `[REDACTED]`; use
+/// [`expose`](Self::expose) to read it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Zeroizing<String>);
+
+impl Secret {
+    /// Wrap `value`.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(Zeroizing::new(value.into()))
+    }
+
+    /// The secret value.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+const REDACTED: &str = "[REDACTED]";
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+/// Source of secrets such as a vault or a cloud secret manager.
+///
+/// Passed to [`BaseConfig::from_env_with`](super::BaseConfig::from_env_with),
+/// it is asked for each secret variable (e.g. `AUTH_TOKEN`) before the
+/// built-in sources.
+pub trait SecretProvider: Send + Sync {
+    /// The secret for the variable `name`, or `None` if this provider has
+    /// no value for it.
+    fn secret(&self, name: &str) -> Result<Option<Secret>, SecretError>;
+}
+
+/// A provider without secrets, leaving everything to the built-in sources.
+pub(crate) struct NoSecrets;
+
+impl SecretProvider for NoSecrets {
+    fn secret(&self, _name: &str) -> Result<Option<Secret>, SecretError> {
+        Ok(None)
+    }
+}
+
+/// Read the secret variable `name` from the first source that has it:
+///
+/// 1. `provider`
+/// 2. the file named by `<name>_FILE`
+/// 3. the systemd credential `<name>` in `$CREDENTIALS_DIRECTORY`
+/// 4. the variable `name` itself
+pub(crate) fn lookup_secret(
+    name: &str,
+    lookup: Lookup<'_>,
+    provider: &dyn SecretProvider,
+) -> Result<Option<Secret>, SecretError> {
+    if let Some(secret) = provider.secret(name)? {
+        return Ok(Some(secret));
+    }
+    if let Some(path) = lookup(&format!("{name}_FILE")) {
+        return read_secret_file(name, Path::new(&path)).map(Some);
+    }
+    if let Some(dir) = lookup("CREDENTIALS_DIRECTORY") {
+        let path = Path::new(&dir).join(name);
+        if path.exists() {
+            return read_secret_file(name, &path).map(Some);
+        }
+    }
+    Ok(lookup(name).map(Secret::from))
+}
+
+/// Read a secret file, without the trailing newline most tools write.
+fn read_secret_file(name: &str, path: &Path) -> Result<Secret, SecretError> {
+    let contents = fs::read_to_string(path).map_err(|source| SecretError {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let contents = Zeroizing::new(contents);
+    Ok(Secret::new(contents.trim_end_matches(['\r', '\n'])))
+}
+
+/// Error returned when a configured secret cannot be read.
+#[derive(Debug)]
+pub struct SecretError {
+    /// Variable the secret is for.
+    pub name: String,
+    /// File the secret should be read from.
+    pub path: PathBuf,
+    /// Why reading failed.
+    pub source: io::Error,
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to read {} from {}: {}",
+            self.name,
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl std::error::Error for SecretError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct Vault;
+
+    impl SecretProvider for Vault {
+        fn secret(&self, name: &str) -> Result<Option<Secret>, SecretError> {
+            Ok((name == "AUTH_TOKEN").then(|| Secret::new("from-vault")))
+        }
+    }
+
+    fn resolve(
+        vars: &HashMap<String, String>,
+        provider: &dyn SecretProvider,
+    ) -> Result<Option<Secret>, SecretError> {
+        lookup_secret("AUTH_TOKEN", &|name| vars.get(name).cloned(), provider)
+    }
+
+    #[test]
+    fn reads_secrets_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("token");
+        fs::write(&file, "from-file\n").unwrap();
+        fs::write(dir.path().join("AUTH_TOKEN"), "from-credentials").unwrap();
+
+        let mut vars = HashMap::from([("AUTH_TOKEN".to_string(), "from-env".to_string())]);
+        let token = |vars: &_| resolve(vars, &NoSecrets).unwrap().unwrap();
+        assert_eq!(token(&vars).expose(), "from-env");
+
+        vars.insert(
+            "CREDENTIALS_DIRECTORY".to_string(),
+            dir.path().display().to_string(),
+        );
+        assert_eq!(token(&vars).expose(), "from-credentials");
+
+        vars.insert("AUTH_TOKEN_FILE".to_string(), file.display().to_string());
+        assert_eq!(token(&vars).expose(), "from-file");
+
+        let secret = resolve(&vars, &Vault).unwrap().unwrap();
+        assert_eq!(secret.expose(), "from-vault");
+    }
+
+    #[test]
+    fn missing_secret_file_is_an_error() {
+        let vars = HashMap::from([(
+            "AUTH_TOKEN_FILE".to_string(),
+            "/nonexistent/token".to_string(),
+        )]);
+        let err = resolve(&vars, &NoSecrets).unwrap_err();
+        assert_eq!(err.name, "AUTH_TOKEN");
+        assert_eq!(err.source.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn output_is_redacted() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(format!("{secret:?}"), "[REDACTED]");
+        assert_eq!(secret.to_string(), "[REDACTED]");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), r#""[REDACTED]""#);
+        assert_eq!(format!("{:?}", Some(secret)), "Some([REDACTED])");
+    }
+}
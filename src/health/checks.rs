@@ -0,0 +1,342 @@
+//! Health check router.
+
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+
+type CheckFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+type Check = Arc<dyn Fn() -> CheckFuture + Send + Sync>;
+
+/// Default [`HealthChecks::timeout`].
+const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Build metadata reported by `/version`.
+///
+/// Use [`build_info!`](crate::build_info) to fill it from the calling crate.
+#[derive(Clone, Debug, Serialize)]
+pub struct BuildInfo {
+    /// Package name.
+    pub name: &'static str,
+    /// Package version.
+    pub version: &'static str,
+    /// Commit the binary was built from (`GIT_SHA` at compile time).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<&'static str>,
+    /// Build timestamp (`BUILD_TIMESTAMP` at compile time).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_time: Option<&'static str>,
+    /// Version of `mcp-core` the binary was built with.
+    pub mcp_core_version: &'static str,
+}
+
+/// Build a [`BuildInfo`] for the crate invoking the macro.
+///
+/// Reads `CARGO_PKG_NAME` and `CARGO_PKG_VERSION`, plus the optional
+/// `GIT_SHA` and `BUILD_TIMESTAMP` environment variables at compile time
+/// (e.g. set from a build script or CI).
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::health::BuildInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: option_env!("GIT_SHA"),
+            build_time: option_env!("BUILD_TIMESTAMP"),
+            mcp_core_version: $crate::health::MCP_CORE_VERSION,
+        }
+    };
+}
+
+/// Version of this crate, used by [`build_info!`](crate::build_info).
+#[doc(hidden)]
+pub const MCP_CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Builder for the health router.
+///
+/// Serves:
+/// - `GET /healthz` - always `200` while the process is serving requests
+/// - `GET /readyz` - `200` if all checks pass, `503` otherwise, with the
+///   status of each check in the body; why a check failed is only logged
+/// - `GET /version` - the configured [`BuildInfo`]
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::{build_info, health::HealthChecks, TokenAuthLayer};
+///
+/// let health = HealthChecks::new(build_info!())
+///     .data_path(&config.data_path)
+///     .check("upstream", || async { ping_upstream().await.map_err(|e| e.to_string()) })
+///     .router();
+///
+/// let app = protected_router
+///     .layer(TokenAuthLayer::new(token))
+///     .merge(health);
+/// ```
+#[derive(Clone)]
+pub struct HealthChecks {
+    build_info: BuildInfo,
+    checks: Vec<(String, Check)>,
+    timeout: Duration,
+}
+
+impl HealthChecks {
+    /// Create a health router reporting the given build metadata.
+    pub fn new(build_info: BuildInfo) -> Self {
+        Self {
+            build_info,
+            checks: Vec::new(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        }
+    }
+
+    /// Require `path` to be a readable directory for readiness.
+    ///
+    /// The directory is checked on the blocking thread pool, so a hung
+    /// mount fails the check after the [`timeout`](Self::timeout).
+    pub fn data_path(self, path: impl Into<PathBuf>) -> Self {
+        let path = Arc::new(path.into());
+        self.check("data_path", move || {
+            let path = path.clone();
+            async move {
+                tokio::task::spawn_blocking(move || check_dir(&path))
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+        })
+    }
+
+    /// How long each check may take before it counts as failed (default
+    /// 1 second).
+    ///
+    /// Checks run concurrently, so `/readyz` answers within about this
+    /// long. Keep it below the probe's own timeout (Kubernetes defaults
+    /// `timeoutSeconds` to 1), or the probe fails before the checks do.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Register a named readiness check.
+    ///
+    /// The check fails readiness by returning `Err` with a short description.
+    pub fn check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.checks.push((
+            name.into(),
+            Arc::new(move || Box::pin(check()) as CheckFuture),
+        ));
+        self
+    }
+
+    /// Build the router serving `/healthz`, `/readyz` and `/version`.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            .route("/version", get(version))
+            .with_state(Arc::new(self))
+    }
+}
+
+fn check_dir(path: &Path) -> Result<(), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    std::fs::read_dir(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct CheckResult {
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+struct Readiness {
+    status: &'static str,
+    checks: BTreeMap<String, CheckResult>,
+}
+
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn readyz(State(health): State<Arc<HealthChecks>>) -> (StatusCode, Json<Readiness>) {
+    let timeout = health.timeout;
+    let results = futures::future::join_all(health.checks.iter().map(|(name, check)| async move {
+        let result = tokio::time::timeout(timeout, check())
+            .await
+            .unwrap_or_else(|_| Err(format!("timed out after {timeout:?}")));
+        (name, result)
+    }))
+    .await;
+
+    let mut ready = true;
+    let mut checks = BTreeMap::new();
+    for (name, result) in results {
+        let status = match result {
+            Ok(()) => "ok",
+            Err(error) => {
+                // Errors may name paths or hosts, so they are not exposed.
+                tracing::warn!(check = %name, %error, "readiness check failed");
+                ready = false;
+                "error"
+            }
+        };
+        checks.insert(name.clone(), CheckResult { status });
+    }
+
+    let (status, label) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (
+        status,
+        Json(Readiness {
+            status: label,
+            checks,
+        }),
+    )
+}
+
+async fn version(State(health): State<Arc<HealthChecks>>) -> Json<BuildInfo> {
+    Json(health.build_info.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::util::ServiceExt;
+
+    async fn get_json(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn healthz_is_always_ok() {
+        let router = HealthChecks::new(build_info!())
+            .check("failing", || async { Err("down".to_string()) })
+            .router();
+        let (status, body) = get_json(router, "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn readyz_reports_each_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = HealthChecks::new(build_info!())
+            .data_path(dir.path())
+            .check("upstream", || async { Ok(()) })
+            .router();
+
+        let (status, body) = get_json(router, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"]["data_path"]["status"], "ok");
+        assert_eq!(body["checks"]["upstream"]["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn readyz_fails_for_missing_data_path() {
+        let router = HealthChecks::new(build_info!())
+            .data_path("/does/not/exist")
+            .check("upstream", || async { Ok(()) })
+            .router();
+
+        let (status, body) = get_json(router, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(
+            body["checks"]["data_path"],
+            serde_json::json!({"status": "error"})
+        );
+        assert!(!body.to_string().contains("/does/not/exist"));
+        assert_eq!(body["checks"]["upstream"]["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn readyz_fails_slow_checks() {
+        let router = HealthChecks::new(build_info!())
+            .timeout(Duration::from_millis(10))
+            .check("slow", || async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(())
+            })
+            .router();
+
+        let (status, body) = get_json(router, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"]["slow"]["status"], "error");
+    }
+
+    #[tokio::test]
+    async fn readyz_runs_checks_concurrently() {
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(())
+        };
+        let router = HealthChecks::new(build_info!())
+            .check("a", slow)
+            .check("b", slow)
+            .check("c", slow)
+            .check("d", slow)
+            .router();
+
+        let started = std::time::Instant::now();
+        let (status, _) = get_json(router, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(started.elapsed() < Duration::from_millis(900));
+    }
+
+    #[tokio::test]
+    async fn version_reports_build_info() {
+        let router = HealthChecks::new(build_info!()).router();
+        let (status, body) = get_json(router, "/version").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "mcp-core");
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(body["mcp_core_version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn stays_public_when_merged_after_auth() {
+        use crate::TokenAuthLayer;
+
+        let router = Router::new()
+            .route("/private", get(|| async { "secret" }))
+            .layer(TokenAuthLayer::new("token".to_string()))
+            .merge(HealthChecks::new(build_info!()).router());
+
+        let (status, _) = get_json(router.clone(), "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        let request = Request::builder()
+            .uri("/private")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
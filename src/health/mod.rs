@@ -0,0 +1,11 @@
+//! Liveness, readiness and version endpoints.
+//!
+//! The routes are meant for orchestrators and load balancers and therefore
+//! unauthenticated. Merge the router *after* layering the auth middleware onto
+//! the protected routes, so the layer does not apply to it.
+
+mod checks;
+
+#[doc(hidden)]
+pub use checks::MCP_CORE_VERSION;
+pub use checks::{BuildInfo, HealthChecks};
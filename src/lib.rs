@@ -5,7 +5,12 @@
 //! - **auth**: Token-based authentication middleware (Bearer and Basic Auth)
 //! - **config**: Configuration management with environment variable support
 //! - **transport**: SSE transport for MCP HTTP mode
-//! - **bootstrap**: Tracing initialization utilities
+//! - **bootstrap**: Tracing initialization utilities, optionally with OTLP export
+//! - **admin**: Admin HTTP routes (runtime log level, session control, dashboard)
+//! - **audit**: Audit log of authentication and session events
+//! - **health**: Liveness, readiness and version endpoints
+//! - **resources**: MCP resources served from the data directory
+//! - **web**: Static file router for web UIs
 //!
 //! # Features
 //!
@@ -13,20 +18,30 @@
 //! - `config` - Configuration utilities (enabled by default)
 //! - `bootstrap` - Tracing setup (enabled by default)
 //! - `transport` - SSE transport for MCP HTTP mode
+//! - `otel` - OpenTelemetry (OTLP) export of spans and logs
+//! - `admin` - Admin HTTP routes
+//! - `audit` - Audit log sinks fed by the auth layer and SSE transport
+//! - `health` - Unauthenticated `/healthz`, `/readyz` and `/version` routes
+//! - `argon2` - Argon2 token hashes for the auth layer
+//! - `reload` - Reload `BaseConfig` on SIGHUP or config file changes
+//! - `resources` - Files in `data_path` as MCP resources
+//! - `watch` - Resource change notifications for SSE sessions
+//! - `web` - Static file router with caching, compression and range support
+//! - `dashboard` - Admin dashboard for sessions, auth failures and config
 //! - `full` - All features
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use mcp_core::{TokenAuthLayer, BaseConfig, init_tracing};
+//! use mcp_core::{auth::TokenStore, TokenAuthLayer, BaseConfig, init_tracing};
 //!
 //! fn main() {
 //!     init_tracing("myserver=debug");
 //!     let config = BaseConfig::from_env();
-//!     let (token, _generated) = config.get_or_generate_token();
 //!
-//!     // Auth middleware
-//!     let router = my_routes().layer(TokenAuthLayer::new(token));
+//!     // Auth middleware accepting AUTH_TOKEN and AUTH_TOKEN_HASH
+//!     let tokens = TokenStore::from_config(&config).unwrap();
+//!     let router = my_routes().layer(TokenAuthLayer::with_store(tokens));
 //! }
 //! ```
 
@@ -42,15 +57,41 @@ pub mod transport;
 #[cfg(feature = "bootstrap")]
 pub mod bootstrap;
 
+#[cfg(feature = "admin")]
+pub mod admin;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(any(feature = "audit", feature = "auth", feature = "web"))]
+mod timestamp;
+
+#[cfg(feature = "health")]
+pub mod health;
+
+#[cfg(any(feature = "resources", feature = "web"))]
+mod mime;
+
+#[cfg(feature = "resources")]
+pub mod resources;
+
+#[cfg(feature = "web")]
+pub mod web;
+
 // Re-exports for convenience
 #[cfg(feature = "auth")]
-pub use auth::{TokenAuthLayer, TokenAuthService};
+pub use auth::{Principal, TokenAuthLayer, TokenAuthService};
 
 #[cfg(feature = "config")]
-pub use config::{generate_random_token, safe_resolve, BaseConfig, SafePathError};
+pub use config::{
+    generate_random_token, safe_resolve, safe_resolve_for_create, BaseConfig, SafePathError,
+};
 
 #[cfg(feature = "transport")]
 pub use transport::{AuthSseServer, SseTransport};
 
 #[cfg(feature = "bootstrap")]
-pub use bootstrap::init_tracing;
+pub use bootstrap::{init_tracing, init_tracing_with, try_init_tracing, TracingOptions};
+
+#[cfg(feature = "otel")]
+pub use bootstrap::init_tracing_otlp;
@@ -0,0 +1,75 @@
+//! MIME types guessed from file extensions.
+
+/// MIME type for `name`, from its extension.
+pub(crate) fn guess(name: &str) -> &'static str {
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "rs" | "py" | "sh" | "c" | "h" | "go" | "java" | "ts" => "text/plain",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/vnd.microsoft.icon",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" => "application/gzip",
+        "wasm" => "application/wasm",
+        "map" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "avif" => "image/avif",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Whether content of type `mime` is text.
+#[cfg_attr(not(feature = "resources"), allow(dead_code))]
+pub(crate) fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime,
+            "application/json"
+                | "application/manifest+json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/toml"
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_types() {
+        assert_eq!(guess("docs/inhalt.html"), "text/html");
+        assert_eq!(guess("DATA.JSON"), "application/json");
+        assert_eq!(guess("logo.svg"), "image/svg+xml");
+        assert_eq!(guess("Makefile"), "application/octet-stream");
+        assert!(is_text(guess("logo.svg")));
+        assert!(!is_text(guess("logo.png")));
+    }
+}
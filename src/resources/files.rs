@@ -0,0 +1,573 @@
+//! Files below a directory as MCP resources.
+
+use std::{
+    fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::read::GzDecoder;
+use rmcp::model::{
+    AnnotateAble, ErrorData, ListResourcesResult, RawResource, ReadResourceResult, ResourceContents,
+};
+
+use crate::config::{safe_resolve_with, BaseConfig, PathPolicy, SafePathError};
+use crate::mime;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const DEFAULT_MAX_SIZE: u64 = 10 << 20;
+
+/// Serves the files below a directory on `resources/list` and
+/// `resources/read`.
+///
+/// Files are listed recursively in path order and named by their path
+/// relative to the base. URIs are `file://` URLs by default, or
+/// `<scheme>:///<path>` with [`scheme`](Self::scheme). Every path is resolved
+/// with [`safe_resolve_with`], so symlinks leading outside the base and
+/// paths denied by the [`PathPolicy`] are neither listed nor read.
+///
+/// Gzip-compressed files are served decompressed under their name without
+/// `.gz`: `inhalt.html.gz` is listed and read as `inhalt.html` with type
+/// `text/html`. Text types are returned as text if they are valid UTF-8,
+/// everything else as a base64 blob.
+///
+/// File I/O is blocking; call it from `spawn_blocking` for large trees.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::resources::FileResources;
+///
+/// impl ServerHandler for MyServer {
+///     async fn list_resources(
+///         &self,
+///         request: PaginatedRequestParam,
+///         _: RequestContext<RoleServer>,
+///     ) -> Result<ListResourcesResult, ErrorData> {
+///         let cursor = request.and_then(|p| p.cursor);
+///         Ok(self.resources.list(cursor.as_deref())?)
+///     }
+///
+///     async fn read_resource(
+///         &self,
+///         request: ReadResourceRequestParam,
+///         _: RequestContext<RoleServer>,
+///     ) -> Result<ReadResourceResult, ErrorData> {
+///         Ok(self.resources.read(&request.uri)?)
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileResources {
+    base: PathBuf,
+    prefix: String,
+    policy: PathPolicy,
+    page_size: usize,
+    max_size: u64,
+}
+
+impl FileResources {
+    /// Serve the files below `base` as `file://` URIs.
+    pub fn new(base: impl AsRef<Path>) -> Result<Self, ResourceError> {
+        let base = base
+            .as_ref()
+            .canonicalize()
+            .map_err(|e| ResourceError::Path(SafePathError::InvalidBase(e)))?;
+        let path = encode(base.to_string_lossy().replace('\\', "/").trim_matches('/'));
+        let prefix = if path.is_empty() {
+            "file:///".to_string()
+        } else {
+            format!("file:///{path}/")
+        };
+        Ok(Self {
+            base,
+            prefix,
+            policy: PathPolicy::default(),
+            page_size: DEFAULT_PAGE_SIZE,
+            max_size: DEFAULT_MAX_SIZE,
+        })
+    }
+
+    /// Serve the files below `config.data_path`.
+    pub fn from_config(config: &BaseConfig) -> Result<Self, ResourceError> {
+        Self::new(&config.data_path)
+    }
+
+    /// Use `<scheme>:///<path>` URIs instead of `file://` URLs.
+    pub fn scheme(mut self, scheme: &str) -> Self {
+        self.prefix = format!("{scheme}:///");
+        self
+    }
+
+    /// Only serve paths allowed by `policy`.
+    pub fn policy(mut self, policy: PathPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Resources per `resources/list` page (default 100).
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Largest (decompressed) file served by `resources/read` (default
+    /// 10 MiB).
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// URI of the file at `path`, relative to the base.
+    pub fn uri(&self, path: &str) -> String {
+        format!("{}{}", self.prefix, encode(path))
+    }
+
+    /// Base directory, canonicalized.
+    #[cfg_attr(not(feature = "watch"), allow(dead_code))]
+    pub(crate) fn base(&self) -> &Path {
+        &self.base
+    }
+
+    /// URI of the resource backed by the file at `path`, unless the path is
+    /// outside the base or denied by the policy.
+    #[cfg_attr(not(feature = "watch"), allow(dead_code))]
+    pub(crate) fn resource_uri(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        self.policy.check(relative, false).ok()?;
+        let name = relative.to_str()?.replace('\\', "/");
+        Some(self.uri(decompressed_name(&name).unwrap_or(&name)))
+    }
+
+    /// One page of resources, starting after `cursor` from a previous page.
+    ///
+    /// The cursor is the last name of the previous page, so files created or
+    /// removed between requests do not shift the following pages. Only the
+    /// directories holding names after the cursor are read, and those denied
+    /// by the policy, such as `.git` with
+    /// [`deny_hidden`](PathPolicy::deny_hidden), are not descended into.
+    pub fn list(&self, cursor: Option<&str>) -> Result<ListResourcesResult, ResourceError> {
+        let after = match cursor {
+            Some(cursor) => Some(
+                decode(cursor)
+                    .filter(|name| !name.is_empty())
+                    .ok_or(ResourceError::InvalidCursor)?,
+            ),
+            None => None,
+        };
+
+        let mut files = Vec::with_capacity(self.page_size + 1);
+        self.walk(&self.base, "", after.as_deref(), &mut files)?;
+        let more = files.len() > self.page_size;
+        files.truncate(self.page_size);
+
+        let next_cursor = more.then(|| files.last().map(|(name, _)| encode(name)));
+        let resources = files
+            .into_iter()
+            .map(|(name, size)| {
+                let mut resource = RawResource::new(self.uri(&name), name.clone());
+                resource.mime_type = Some(mime::guess(&name).to_string());
+                resource.size = size.and_then(|size| u32::try_from(size).ok());
+                resource.no_annotation()
+            })
+            .collect();
+        Ok(ListResourcesResult {
+            next_cursor: next_cursor.flatten(),
+            resources,
+        })
+    }
+
+    /// Whether `uri` names a resource that [`read`](Self::read) would find,
+    /// e.g. for `SseServerConfig::subscribable`.
+    pub fn serves(&self, uri: &str) -> bool {
+        let Some(name) = uri.strip_prefix(&self.prefix).and_then(decode) else {
+            return false;
+        };
+        [name.clone(), format!("{name}.gz")]
+            .iter()
+            .any(|name| self.resolve(name).is_ok())
+    }
+
+    /// Contents of the resource at `uri`.
+    pub fn read(&self, uri: &str) -> Result<ReadResourceResult, ResourceError> {
+        let not_found = || ResourceError::NotFound(uri.to_string());
+        let name = uri
+            .strip_prefix(&self.prefix)
+            .and_then(decode)
+            .ok_or_else(not_found)?;
+
+        let data = match self.resolve(&name) {
+            Ok(path) => self.read_limited(fs::File::open(path)?)?,
+            Err(SafePathError::NotFound(_)) => {
+                let path = self
+                    .resolve(&format!("{name}.gz"))
+                    .map_err(|_| not_found())?;
+                self.read_limited(GzDecoder::new(fs::File::open(path)?))?
+            }
+            Err(e) => return Err(ResourceError::Path(e)),
+        };
+
+        let mime_type = mime::guess(&name);
+        let contents = match String::from_utf8(data) {
+            Ok(text) if mime::is_text(mime_type) => ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+            },
+            Ok(text) => blob(uri, mime_type, text.as_bytes()),
+            Err(e) => blob(uri, mime_type, e.as_bytes()),
+        };
+        Ok(ReadResourceResult {
+            contents: vec![contents],
+        })
+    }
+
+    /// Resolve `name` to a regular file.
+    fn resolve(&self, name: &str) -> Result<PathBuf, SafePathError> {
+        let path = safe_resolve_with(&self.base, name, &self.policy)?;
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(SafePathError::NotFound(io::ErrorKind::NotFound.into()))
+        }
+    }
+
+    /// Add the files below `dir` named after `after` to `files` in name
+    /// order, with their size unless they are compressed, until it holds one
+    /// more than a page.
+    fn walk(
+        &self,
+        dir: &Path,
+        prefix: &str,
+        after: Option<&str>,
+        files: &mut Vec<(String, Option<u64>)>,
+    ) -> Result<(), ResourceError> {
+        // Directories sort by their name with a trailing `/`, so that the
+        // walk yields names in the same order as comparing them as strings.
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let name = format!("{prefix}{file_name}");
+            if entry.file_type()?.is_dir() {
+                if self.policy.check_dir(Path::new(&name)).is_ok() {
+                    entries.push((format!("{name}/"), false, entry.path()));
+                }
+                continue;
+            }
+            match decompressed_name(&name) {
+                Some(plain) => entries.push((plain.to_string(), true, entry.path())),
+                None => entries.push((name, false, entry.path())),
+            }
+        }
+        // A plain file sorts before its compressed variant, which it hides.
+        entries.sort_unstable();
+
+        for (key, compressed, path) in entries {
+            if files.len() > self.page_size {
+                break;
+            }
+            if key.ends_with('/') {
+                // Every name below the directory starts with `key`.
+                let listed = after.is_some_and(|after| *after > *key && !after.starts_with(&key));
+                if !listed {
+                    self.walk(&path, &key, after, files)?;
+                }
+                continue;
+            }
+            if after.is_some_and(|after| *key <= *after)
+                || files.last().is_some_and(|(last, _)| *last == key)
+            {
+                continue;
+            }
+            let name = if compressed {
+                format!("{key}.gz")
+            } else {
+                key.clone()
+            };
+            let Ok(path) = self.resolve(&name) else {
+                continue;
+            };
+            let size = if compressed {
+                None
+            } else {
+                Some(fs::metadata(path)?.len())
+            };
+            files.push((key, size));
+        }
+        Ok(())
+    }
+
+    /// Read `reader` up to the size limit.
+    fn read_limited(&self, reader: impl Read) -> Result<Vec<u8>, ResourceError> {
+        let mut data = Vec::new();
+        reader
+            .take(self.max_size.saturating_add(1))
+            .read_to_end(&mut data)?;
+        if data.len() as u64 > self.max_size {
+            return Err(ResourceError::TooLarge {
+                limit: self.max_size,
+            });
+        }
+        Ok(data)
+    }
+}
+
+/// Name a gzip-compressed file is served under.
+fn decompressed_name(name: &str) -> Option<&str> {
+    name.strip_suffix(".gz")
+        .filter(|plain| !plain.is_empty() && !plain.ends_with('/'))
+}
+
+fn blob(uri: &str, mime_type: &str, data: &[u8]) -> ResourceContents {
+    ResourceContents::BlobResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some(mime_type.to_string()),
+        blob: STANDARD.encode(data),
+    }
+}
+
+/// Percent-encode everything but unreserved characters and `/`.
+fn encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Decode a percent-encoded path, if it is valid UTF-8.
+fn decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Error returned when listing or reading resources fails.
+#[derive(Debug)]
+pub enum ResourceError {
+    /// No resource has this URI.
+    NotFound(String),
+    /// The path was rejected.
+    Path(SafePathError),
+    /// Reading the directory or file failed.
+    Io(io::Error),
+    /// The file is larger than the configured limit.
+    TooLarge {
+        /// Largest size served, in bytes.
+        limit: u64,
+    },
+    /// The pagination cursor was not returned by a previous page.
+    InvalidCursor,
+}
+
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(uri) => write!(f, "resource not found: {uri}"),
+            Self::Path(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "failed to read resource: {e}"),
+            Self::TooLarge { limit } => write!(f, "resource is larger than {limit} bytes"),
+            Self::InvalidCursor => write!(f, "invalid cursor"),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Path(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ResourceError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ResourceError> for ErrorData {
+    fn from(e: ResourceError) -> Self {
+        match e {
+            ResourceError::NotFound(_) | ResourceError::Path(_) => {
+                ErrorData::resource_not_found(e.to_string(), None)
+            }
+            ResourceError::TooLarge { .. } => ErrorData::invalid_request(e.to_string(), None),
+            ResourceError::InvalidCursor => ErrorData::invalid_params(e.to_string(), None),
+            ResourceError::Io(_) => ErrorData::internal_error(e.to_string(), None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn text(result: &ReadResourceResult) -> (&str, Option<&str>) {
+        match &result.contents[0] {
+            ResourceContents::TextResourceContents {
+                text, mime_type, ..
+            } => (text, mime_type.as_deref()),
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/read me.md"), "# Hello").unwrap();
+        fs::write(dir.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0xff]).unwrap();
+        fs::write(dir.path().join(".env"), "SECRET=1").unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"<html>Inhalt</html>").unwrap();
+        fs::write(dir.path().join("inhalt.html.gz"), gz.finish().unwrap()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn lists_and_reads_files() {
+        let dir = fixture();
+        let resources = FileResources::new(dir.path())
+            .unwrap()
+            .scheme("data")
+            .policy(PathPolicy::new().deny_hidden(true));
+
+        let list = resources.list(None).unwrap();
+        let names: Vec<_> = list.resources.iter().map(|r| r.raw.name.as_str()).collect();
+        assert_eq!(names, ["docs/read me.md", "inhalt.html", "logo.png"]);
+        assert_eq!(list.resources[0].raw.uri, "data:///docs/read%20me.md");
+        assert_eq!(list.resources[0].raw.size, Some(7));
+        assert_eq!(
+            list.resources[1].raw.mime_type.as_deref(),
+            Some("text/html")
+        );
+        assert_eq!(list.resources[1].raw.size, None);
+
+        let doc = resources.read("data:///docs/read%20me.md").unwrap();
+        assert_eq!(text(&doc), ("# Hello", Some("text/markdown")));
+
+        let html = resources.read("data:///inhalt.html").unwrap();
+        assert_eq!(text(&html), ("<html>Inhalt</html>", Some("text/html")));
+
+        let logo = resources.read("data:///logo.png").unwrap();
+        assert!(matches!(
+            &logo.contents[0],
+            ResourceContents::BlobResourceContents { blob, .. } if blob == "iVBOR/8="
+        ));
+
+        assert!(matches!(
+            resources.read("data:///.env"),
+            Err(ResourceError::Path(SafePathError::Hidden))
+        ));
+        assert!(matches!(
+            resources.read("data:///.."),
+            Err(ResourceError::Path(SafePathError::PathTraversal))
+        ));
+        assert!(matches!(
+            resources.read("file:///logo.png"),
+            Err(ResourceError::NotFound(_))
+        ));
+
+        assert!(resources.serves("data:///docs/read%20me.md"));
+        assert!(resources.serves("data:///inhalt.html"));
+        for uri in [
+            "data:///docs",
+            "data:///.env",
+            "data:///missing",
+            "file:///logo.png",
+        ] {
+            assert!(!resources.serves(uri), "{uri}");
+        }
+    }
+
+    #[test]
+    fn paginates_listings() {
+        let dir = fixture();
+        let resources = FileResources::new(dir.path()).unwrap().page_size(2);
+
+        let first = resources.list(None).unwrap();
+        assert_eq!(first.resources.len(), 2);
+        let second = resources.list(first.next_cursor.as_deref()).unwrap();
+        assert_eq!(second.resources.len(), 2);
+        assert_eq!(second.next_cursor, None);
+        assert!(second.resources[1].raw.uri.starts_with(&format!(
+            "file://{}",
+            encode(&dir.path().canonicalize().unwrap().to_string_lossy())
+        )));
+        assert!(matches!(
+            resources.list(Some("%zz")),
+            Err(ResourceError::InvalidCursor)
+        ));
+    }
+
+    #[test]
+    fn continues_after_the_cursor_name() {
+        let dir = fixture();
+        fs::create_dir_all(dir.path().join(".git/objects")).unwrap();
+        fs::write(dir.path().join(".git/objects/pack"), "").unwrap();
+        fs::write(dir.path().join("docs.txt"), "").unwrap();
+        fs::write(dir.path().join("logo.png.gz"), "").unwrap();
+        let resources = FileResources::new(dir.path())
+            .unwrap()
+            .page_size(2)
+            .policy(PathPolicy::new().deny_hidden(true));
+
+        let first = resources.list(None).unwrap();
+        let names: Vec<_> = first
+            .resources
+            .iter()
+            .map(|r| r.raw.name.as_str())
+            .collect();
+        assert_eq!(names, ["docs.txt", "docs/read me.md"]);
+        assert_eq!(first.next_cursor.as_deref(), Some("docs/read%20me.md"));
+
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::remove_file(dir.path().join("docs/read me.md")).unwrap();
+        let second = resources.list(first.next_cursor.as_deref()).unwrap();
+        let names: Vec<_> = second
+            .resources
+            .iter()
+            .map(|r| r.raw.name.as_str())
+            .collect();
+        assert_eq!(names, ["inhalt.html", "logo.png"]);
+        assert_eq!(second.resources[1].raw.size, Some(5));
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[test]
+    fn enforces_size_limit() {
+        let dir = fixture();
+        let resources = FileResources::new(dir.path()).unwrap().max_size(8);
+        assert!(resources.read(&resources.uri("docs/read me.md")).is_ok());
+        assert!(matches!(
+            resources.read(&resources.uri("inhalt.html")),
+            Err(ResourceError::TooLarge { limit: 8 })
+        ));
+    }
+}
@@ -0,0 +1,14 @@
+//! MCP resources backed by files in the data directory.
+//!
+//! [`FileResources`] answers `resources/list` and `resources/read` from the
+//! files below a directory, usually [`BaseConfig::data_path`](crate::BaseConfig).
+//! With the `watch` feature, [`watch_resources`] notifies subscribed SSE
+//! sessions when those files change.
+
+mod files;
+#[cfg(feature = "watch")]
+mod watch;
+
+pub use files::{FileResources, ResourceError};
+#[cfg(feature = "watch")]
+pub use watch::{watch_resources, ResourceWatcher};
@@ -0,0 +1,235 @@
+//! Resource change notifications from filesystem events.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use notify::{
+    event::ModifyKind, Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use super::FileResources;
+use crate::transport::ResourceNotifier;
+
+/// Running watcher started by [`watch_resources`]; stops when dropped.
+pub struct ResourceWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for ResourceWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Watch the files served by `resources` and notify SSE sessions of changes.
+///
+/// Uses inotify on Linux (and the native API elsewhere) on the base
+/// directory, without following symlinks out of it. Events within
+/// `debounce` of the first one are collected, then subscribed sessions get
+/// `notifications/resources/updated` once per changed resource, and all
+/// sessions get `notifications/resources/list_changed` if files were
+/// created, removed or renamed. Files denied by the resources' policy are
+/// ignored.
+///
+/// Must be called within a tokio runtime.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::resources::{watch_resources, FileResources};
+///
+/// let (mut server, router) = AuthSseServer::with_config(SseServerConfig {
+///     resource_subscriptions: true,
+///     ..Default::default()
+/// });
+/// let resources = FileResources::from_config(&config)?;
+/// let _watcher = watch_resources(
+///     resources.clone(),
+///     server.resource_notifier(),
+///     Duration::from_millis(250),
+/// )?;
+/// ```
+pub fn watch_resources(
+    resources: FileResources,
+    notifier: ResourceNotifier,
+    debounce: Duration,
+) -> Result<ResourceWatcher, notify::Error> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = RecommendedWatcher::new(
+        move |event| {
+            let _ = tx.send(event);
+        },
+        Config::default().with_follow_symlinks(false),
+    )?;
+    watcher.watch(resources.base(), RecursiveMode::Recursive)?;
+
+    let task = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let mut changes = Changes::default();
+            changes.add(&resources, event);
+            let deadline = tokio::time::sleep(debounce);
+            tokio::pin!(deadline);
+            loop {
+                tokio::select! {
+                    _ = &mut deadline => break,
+                    event = rx.recv() => match event {
+                        Some(event) => changes.add(&resources, event),
+                        None => break,
+                    },
+                }
+            }
+            changes.notify(&notifier).await;
+        }
+    });
+    Ok(ResourceWatcher {
+        _watcher: watcher,
+        task,
+    })
+}
+
+/// Changes collected during one debounce interval.
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    updated: BTreeSet<String>,
+    list_changed: bool,
+}
+
+impl Changes {
+    fn add(&mut self, resources: &FileResources, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!(error = %e, "resource watcher error");
+                return;
+            }
+        };
+        let list_changed = match event.kind {
+            EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(ModifyKind::Name(_)) => true,
+            EventKind::Modify(ModifyKind::Metadata(_)) => return,
+            EventKind::Modify(_) => false,
+            _ => return,
+        };
+        for uri in event
+            .paths
+            .iter()
+            .filter_map(|path| resources.resource_uri(path))
+        {
+            self.list_changed |= list_changed;
+            self.updated.insert(uri);
+        }
+    }
+
+    async fn notify(self, notifier: &ResourceNotifier) {
+        if self.list_changed {
+            notifier.resource_list_changed().await;
+        }
+        for uri in &self.updated {
+            let sessions = notifier.resource_updated(uri).await;
+            tracing::debug!(uri, sessions, "resource changed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PathPolicy;
+    use crate::transport::{AuthSseServer, SseServerConfig};
+    use axum::{
+        body::{Body, BodyDataStream},
+        http::Request,
+    };
+    use futures::StreamExt;
+    use notify::event::{CreateKind, DataChange};
+    use tower::util::ServiceExt;
+
+    #[test]
+    fn collects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let resources = FileResources::new(dir.path())
+            .unwrap()
+            .scheme("data")
+            .policy(PathPolicy::new().deny_hidden(true));
+        let base = resources.base().to_path_buf();
+        let event = |kind, name: &str| Ok(Event::new(kind).add_path(base.join(name)));
+
+        let mut changes = Changes::default();
+        changes.add(
+            &resources,
+            event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), "a.md"),
+        );
+        changes.add(
+            &resources,
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                ".a.md.swp",
+            ),
+        );
+        assert_eq!(
+            changes.updated,
+            BTreeSet::from(["data:///a.md".to_string()])
+        );
+        assert!(!changes.list_changed);
+
+        changes.add(
+            &resources,
+            event(EventKind::Create(CreateKind::File), "docs/inhalt.html.gz"),
+        );
+        assert!(changes.updated.contains("data:///docs/inhalt.html"));
+        assert!(changes.list_changed);
+    }
+
+    /// The data of the next SSE event.
+    async fn next_data(events: &mut BodyDataStream) -> String {
+        let chunk = events.next().await.unwrap().unwrap();
+        let text = String::from_utf8(chunk.to_vec()).unwrap();
+        text.lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn notifies_sessions_of_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "one").unwrap();
+        let resources = FileResources::new(dir.path()).unwrap().scheme("data");
+
+        let (server, router) = AuthSseServer::with_config(SseServerConfig {
+            resource_subscriptions: true,
+            ..Default::default()
+        });
+        let _watcher = watch_resources(
+            resources,
+            server.resource_notifier(),
+            Duration::from_millis(50),
+        )
+        .unwrap();
+
+        let request = Request::builder().uri("/sse").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let mut events = response.into_body().into_data_stream();
+        let endpoint = next_data(&mut events).await;
+
+        let subscribe = r#"{"jsonrpc":"2.0","id":1,"method":"resources/subscribe",
+            "params":{"uri":"data:///a.md"}}"#;
+        let request = Request::builder()
+            .method("POST")
+            .uri(endpoint)
+            .header("Content-Type", "application/json")
+            .body(Body::from(subscribe))
+            .unwrap();
+        router.clone().oneshot(request).await.unwrap();
+        assert!(next_data(&mut events).await.contains(r#""id":1"#));
+
+        std::fs::write(dir.path().join("a.md"), "two").unwrap();
+        let update = tokio::time::timeout(Duration::from_secs(5), next_data(&mut events))
+            .await
+            .unwrap();
+        assert!(update.contains("notifications/resources/updated"));
+        assert!(update.contains("data:///a.md"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Helpers for inspecting JSON-RPC messages received from MCP clients.
+
+use rmcp::model::{
+    ClientJsonRpcMessage, ClientNotification, ClientRequest, ConstString, EmptyResult, ErrorCode,
+    ErrorData, JsonRpcError, JsonRpcNotification, JsonRpcResponse, JsonRpcVersion2_0, Notification,
+    NotificationNoParam, RequestId, ResourceUpdatedNotificationParam, ServerJsonRpcMessage,
+    ServerNotification, ServerResult,
+};
+
+/// Return the JSON-RPC method name of a client request.
+pub(crate) fn request_method(request: &ClientRequest) -> &'static str {
+    fn value<M: ConstString>(_: &M) -> &'static str {
+        M::VALUE
+    }
+
+    match request {
+        ClientRequest::PingRequest(r) => value(&r.method),
+        ClientRequest::InitializeRequest(r) => value(&r.method),
+        ClientRequest::CompleteRequest(r) => value(&r.method),
+        ClientRequest::SetLevelRequest(r) => value(&r.method),
+        ClientRequest::GetPromptRequest(r) => value(&r.method),
+        ClientRequest::ListPromptsRequest(r) => value(&r.method),
+        ClientRequest::ListResourcesRequest(r) => value(&r.method),
+        ClientRequest::ListResourceTemplatesRequest(r) => value(&r.method),
+        ClientRequest::ReadResourceRequest(r) => value(&r.method),
+        ClientRequest::SubscribeRequest(r) => value(&r.method),
+        ClientRequest::UnsubscribeRequest(r) => value(&r.method),
+        ClientRequest::CallToolRequest(r) => value(&r.method),
+        ClientRequest::ListToolsRequest(r) => value(&r.method),
+    }
+}
+
+/// Return the JSON-RPC method name of a server notification.
+pub(crate) fn notification_method(notification: &ServerNotification) -> &'static str {
+    fn value<M: ConstString>(_: &M) -> &'static str {
+        M::VALUE
+    }
+
+    match notification {
+        ServerNotification::CancelledNotification(n) => value(&n.method),
+        ServerNotification::ProgressNotification(n) => value(&n.method),
+        ServerNotification::LoggingMessageNotification(n) => value(&n.method),
+        ServerNotification::ResourceUpdatedNotification(n) => value(&n.method),
+        ServerNotification::ResourceListChangedNotification(n) => value(&n.method),
+        ServerNotification::ToolListChangedNotification(n) => value(&n.method),
+        ServerNotification::PromptListChangedNotification(n) => value(&n.method),
+    }
+}
+
+/// Return the method name and id if the message is a request.
+pub(crate) fn request_info(message: &ClientJsonRpcMessage) -> Option<(&'static str, &RequestId)> {
+    match message {
+        ClientJsonRpcMessage::Request(r) => Some((request_method(&r.request), &r.id)),
+        _ => None,
+    }
+}
+
+/// Id of the request cancelled by a `notifications/cancelled` message.
+pub(crate) fn cancelled_request(message: &ClientJsonRpcMessage) -> Option<&RequestId> {
+    match message {
+        ClientJsonRpcMessage::Notification(n) => match &n.notification {
+            ClientNotification::CancelledNotification(c) => Some(&c.params.request_id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Scope a principal needs for the request, if it is scoped at all.
+///
+/// - `tools/call` needs `tool:<name>`
+/// - `resources/read` and `resources/subscribe` need `resource:<uri>`
+/// - `prompts/get` needs `prompt:<name>`
+pub(crate) fn required_scope(message: &ClientJsonRpcMessage) -> Option<(&RequestId, String)> {
+    let ClientJsonRpcMessage::Request(request) = message else {
+        return None;
+    };
+    let scope = match &request.request {
+        ClientRequest::CallToolRequest(r) => format!("tool:{}", r.params.name),
+        ClientRequest::ReadResourceRequest(r) => format!("resource:{}", r.params.uri),
+        ClientRequest::SubscribeRequest(r) => format!("resource:{}", r.params.uri),
+        ClientRequest::GetPromptRequest(r) => format!("prompt:{}", r.params.name),
+        _ => return None,
+    };
+    Some((&request.id, scope))
+}
+
+/// A `resources/subscribe` or `resources/unsubscribe` request.
+pub(crate) enum Subscription<'a> {
+    Subscribe(&'a str),
+    Unsubscribe(&'a str),
+}
+
+/// Return the id and subscription change if the message is a
+/// `resources/subscribe` or `resources/unsubscribe` request.
+pub(crate) fn subscription(
+    message: &ClientJsonRpcMessage,
+) -> Option<(&RequestId, Subscription<'_>)> {
+    let ClientJsonRpcMessage::Request(request) = message else {
+        return None;
+    };
+    let subscription = match &request.request {
+        ClientRequest::SubscribeRequest(r) => Subscription::Subscribe(&r.params.uri),
+        ClientRequest::UnsubscribeRequest(r) => Subscription::Unsubscribe(&r.params.uri),
+        _ => return None,
+    };
+    Some((&request.id, subscription))
+}
+
+/// Implementation-defined JSON-RPC server error for requests over the
+/// in-flight limit.
+const TOO_MANY_REQUESTS: ErrorCode = ErrorCode(-32000);
+
+fn error(id: RequestId, error: ErrorData) -> ServerJsonRpcMessage {
+    ServerJsonRpcMessage::Error(JsonRpcError {
+        jsonrpc: JsonRpcVersion2_0,
+        id,
+        error,
+    })
+}
+
+/// JSON-RPC error answering a request the caller lacks `scope` for.
+pub(crate) fn forbidden(id: RequestId, scope: &str) -> ServerJsonRpcMessage {
+    error(
+        id,
+        ErrorData::invalid_request(
+            format!("missing scope {scope}"),
+            Some(serde_json::json!({ "requiredScope": scope })),
+        ),
+    )
+}
+
+/// JSON-RPC error answering a request whose id is already in flight.
+pub(crate) fn duplicate_request(id: RequestId) -> ServerJsonRpcMessage {
+    error(
+        id,
+        ErrorData::invalid_request("request id already in flight", None),
+    )
+}
+
+/// JSON-RPC error answering a request over the in-flight limit.
+pub(crate) fn too_many_requests(id: RequestId) -> ServerJsonRpcMessage {
+    error(
+        id,
+        ErrorData::new(TOO_MANY_REQUESTS, "too many requests in flight", None),
+    )
+}
+
+/// JSON-RPC error answering a subscription to a resource that is not served.
+pub(crate) fn unknown_resource(id: RequestId, uri: &str) -> ServerJsonRpcMessage {
+    error(
+        id,
+        ErrorData::resource_not_found(
+            "resource not found",
+            Some(serde_json::json!({ "uri": uri })),
+        ),
+    )
+}
+
+/// JSON-RPC error answering a subscription over the per-session limit.
+pub(crate) fn too_many_subscriptions(id: RequestId, max: usize) -> ServerJsonRpcMessage {
+    error(
+        id,
+        ErrorData::invalid_request(format!("at most {max} subscriptions per session"), None),
+    )
+}
+
+/// Empty JSON-RPC result answering request `id`.
+pub(crate) fn empty_result(id: RequestId) -> ServerJsonRpcMessage {
+    ServerJsonRpcMessage::Response(JsonRpcResponse {
+        jsonrpc: JsonRpcVersion2_0,
+        id,
+        result: ServerResult::EmptyResult(EmptyResult {}),
+    })
+}
+
+/// JSON-RPC message carrying `notification`.
+pub(crate) fn notification(notification: ServerNotification) -> ServerJsonRpcMessage {
+    ServerJsonRpcMessage::Notification(JsonRpcNotification {
+        jsonrpc: JsonRpcVersion2_0,
+        notification,
+    })
+}
+
+/// `notifications/resources/updated` for `uri`.
+pub(crate) fn resource_updated(uri: &str) -> ServerJsonRpcMessage {
+    notification(ServerNotification::ResourceUpdatedNotification(
+        Notification {
+            method: Default::default(),
+            params: ResourceUpdatedNotificationParam {
+                uri: uri.to_string(),
+            },
+        },
+    ))
+}
+
+/// `notifications/resources/list_changed`.
+pub(crate) fn resource_list_changed() -> ServerJsonRpcMessage {
+    notification(ServerNotification::ResourceListChangedNotification(
+        NotificationNoParam {
+            method: Default::default(),
+        },
+    ))
+}
+
+/// Id of a response or error sent to the client.
+pub(crate) fn response_id(message: &ServerJsonRpcMessage) -> Option<&RequestId> {
+    match message {
+        ServerJsonRpcMessage::Response(r) => Some(&r.id),
+        ServerJsonRpcMessage::Error(e) => Some(&e.id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ClientJsonRpcMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn extracts_method_and_id_from_request() {
+        let message =
+            parse(r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"search"}}"#);
+        let (method, id) = request_info(&message).unwrap();
+        assert_eq!(method, "tools/call");
+        assert_eq!(id.to_string(), "7");
+    }
+
+    #[test]
+    fn extracts_method_of_parameterless_request() {
+        let message = parse(r#"{"jsonrpc":"2.0","id":"a","method":"ping"}"#);
+        assert_eq!(request_info(&message).unwrap().0, "ping");
+    }
+
+    #[test]
+    fn notifications_have_no_request_info() {
+        let message = parse(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#);
+        assert!(request_info(&message).is_none());
+    }
+
+    #[test]
+    fn derives_scope_of_scoped_requests() {
+        let scope = |json| required_scope(&parse(json)).map(|(_, scope)| scope);
+        assert_eq!(
+            scope(r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"search"}}"#)
+                .as_deref(),
+            Some("tool:search")
+        );
+        assert_eq!(
+            scope(
+                r#"{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"file:///a"}}"#
+            )
+            .as_deref(),
+            Some("resource:file:///a")
+        );
+        assert_eq!(
+            scope(r#"{"jsonrpc":"2.0","id":3,"method":"prompts/get","params":{"name":"hi"}}"#)
+                .as_deref(),
+            Some("prompt:hi")
+        );
+        assert_eq!(
+            scope(
+                r#"{"jsonrpc":"2.0","id":4,"method":"resources/subscribe","params":{"uri":"file:///a"}}"#
+            )
+            .as_deref(),
+            Some("resource:file:///a")
+        );
+        assert!(scope(r#"{"jsonrpc":"2.0","id":5,"method":"tools/list"}"#).is_none());
+    }
+
+    #[test]
+    fn serializes_resource_notifications() {
+        let json = |message| serde_json::to_value(message).unwrap();
+        assert_eq!(
+            json(resource_updated("file:///a")),
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": {"uri": "file:///a"},
+            })
+        );
+        assert_eq!(
+            json(resource_list_changed())["method"],
+            "notifications/resources/list_changed"
+        );
+    }
+}
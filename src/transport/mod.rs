@@ -3,6 +3,13 @@
 //! Provides a custom SSE server implementation that can be wrapped with
 //! authentication middleware.
 
+mod message;
+mod rate_limit;
 mod sse;
 
-pub use sse::{AuthSseServer, SseTransport};
+#[cfg(feature = "admin")]
+pub(crate) use message::notification_method;
+pub use sse::{
+    AuthSseServer, ResourceFilter, ResourceNotifier, SessionInfo, Sessions, SseServerConfig,
+    SseTransport,
+};
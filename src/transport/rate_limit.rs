@@ -0,0 +1,372 @@
+//! Token-bucket and in-flight limits for messages posted to SSE sessions.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::config::{RateLimit, RateLimits};
+
+/// Refills at `rate` tokens per second up to `capacity`.
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit) -> Option<Self> {
+        let capacity = limit.bucket_size()?;
+        Some(Self {
+            rate: limit.requests_per_second?,
+            capacity,
+            tokens: capacity,
+            updated: Instant::now(),
+        })
+    }
+
+    /// Take a token, or return how long until one is available.
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+
+    /// Whether the bucket has refilled completely by `now`.
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.rate >= self.capacity
+    }
+}
+
+/// How often idle principals are dropped from the limiter.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Number of requests forwarded to the MCP service and not yet answered.
+#[derive(Clone, Default)]
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn is_empty(&self) -> bool {
+        self.0.load(Ordering::Acquire) == 0
+    }
+
+    fn try_acquire(&self, max: Option<usize>) -> bool {
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| match max {
+                Some(max) if n >= max => None,
+                _ => Some(n + 1),
+            })
+            .is_ok()
+    }
+}
+
+/// Slot of an in-flight request, released when dropped.
+pub(crate) struct Permit(Vec<InFlight>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        for in_flight in &self.0 {
+            in_flight.0.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Limiter state of one principal or session.
+struct LimitState {
+    limit: RateLimit,
+    bucket: Option<TokenBucket>,
+    in_flight: InFlight,
+}
+
+impl LimitState {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            bucket: TokenBucket::new(&limit),
+            limit,
+            in_flight: InFlight::default(),
+        }
+    }
+
+    /// Switch to `limit`, keeping the requests in flight.
+    fn update(&mut self, limit: RateLimit) {
+        if self.limit != limit {
+            self.bucket = TokenBucket::new(&limit);
+            self.limit = limit;
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        match &mut self.bucket {
+            Some(bucket) => bucket.try_acquire(now),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether dropping the state would change no limit: nothing is in
+    /// flight and the bucket is as full as a new one.
+    fn is_idle(&self, now: Instant) -> bool {
+        self.in_flight.is_empty() && self.bucket.as_ref().is_none_or(|b| b.is_full(now))
+    }
+}
+
+/// Limiter state of every principal seen since it was last idle.
+struct Principals {
+    states: HashMap<Arc<str>, LimitState>,
+    swept: Instant,
+}
+
+impl Principals {
+    /// State of `name`, created with `limit` if it has none.
+    ///
+    /// Idle states of other principals are dropped at most once per
+    /// [`SWEEP_INTERVAL`], so the map only holds recently active principals.
+    fn get(&mut self, name: &str, limit: RateLimit, now: Instant) -> &mut LimitState {
+        if now.saturating_duration_since(self.swept) >= SWEEP_INTERVAL {
+            self.states.retain(|_, state| !state.is_idle(now));
+            self.swept = now;
+        }
+        self.states
+            .entry(Arc::from(name))
+            .or_insert_with(|| LimitState::new(limit))
+    }
+}
+
+/// Limits of one session, checked together with its principal's.
+pub(crate) struct SessionLimiter(Mutex<LimitState>);
+
+/// Why a message was rejected.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Limited {
+    /// Over the request rate; retry after the given delay.
+    Rate(Duration),
+    /// Too many requests awaiting a response.
+    InFlight,
+}
+
+/// Shared limiter for all sessions of an SSE server.
+pub(crate) struct RateLimiter {
+    limits: RwLock<RateLimits>,
+    principals: Mutex<Principals>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Self {
+        Self {
+            limits: RwLock::new(limits),
+            principals: Mutex::new(Principals {
+                states: HashMap::new(),
+                swept: Instant::now(),
+            }),
+        }
+    }
+
+    fn limits(&self) -> RateLimits {
+        *self.limits.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Apply new limits to all principals and sessions.
+    ///
+    /// Buckets start full again; requests already in flight keep counting.
+    #[cfg_attr(not(feature = "reload"), allow(dead_code))]
+    pub(crate) fn set_limits(&self, limits: RateLimits) {
+        let mut current = self.limits.write().unwrap_or_else(|e| e.into_inner());
+        if *current == limits {
+            return;
+        }
+        *current = limits;
+        let mut principals = self.principals.lock().unwrap_or_else(|e| e.into_inner());
+        for state in principals.states.values_mut() {
+            state.update(limits.per_principal);
+        }
+    }
+
+    /// State for a new session.
+    pub(crate) fn session(&self) -> SessionLimiter {
+        SessionLimiter(Mutex::new(LimitState::new(self.limits().per_session)))
+    }
+
+    /// Count a message against the request rate of `principal` and `session`.
+    pub(crate) fn check_rate(
+        &self,
+        principal: Option<&str>,
+        session: &SessionLimiter,
+    ) -> Result<(), Limited> {
+        let now = Instant::now();
+        let limits = self.limits();
+        let mut session = session.0.lock().unwrap_or_else(|e| e.into_inner());
+        session.update(limits.per_session);
+        session.try_acquire(now).map_err(Limited::Rate)?;
+        match principal {
+            Some(name) => self.check_principal_rate(name),
+            None => Ok(()),
+        }
+    }
+
+    /// Count opening a session or posting a message against the request
+    /// rate of `principal`.
+    pub(crate) fn check_principal_rate(&self, principal: &str) -> Result<(), Limited> {
+        let limit = self.limits().per_principal;
+        if limit.requests_per_second.is_none() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let mut principals = self.principals.lock().unwrap_or_else(|e| e.into_inner());
+        principals
+            .get(principal, limit, now)
+            .try_acquire(now)
+            .map_err(Limited::Rate)
+    }
+
+    /// Reserve an in-flight slot for a request of `principal` in `session`.
+    pub(crate) fn acquire(
+        &self,
+        principal: Option<&str>,
+        session: &SessionLimiter,
+    ) -> Result<Permit, Limited> {
+        let limits = self.limits();
+        let mut permit = Permit(Vec::with_capacity(2));
+        {
+            let mut session = session.0.lock().unwrap_or_else(|e| e.into_inner());
+            session.update(limits.per_session);
+            if !session.in_flight.try_acquire(session.limit.max_in_flight) {
+                return Err(Limited::InFlight);
+            }
+            permit.0.push(session.in_flight.clone());
+        }
+        if let Some(name) = principal {
+            let max = limits.per_principal.max_in_flight;
+            if max.is_some() {
+                let mut principals = self.principals.lock().unwrap_or_else(|e| e.into_inner());
+                let state = principals.get(name, limits.per_principal, Instant::now());
+                if !state.in_flight.try_acquire(max) {
+                    return Err(Limited::InFlight);
+                }
+                permit.0.push(state.in_flight.clone());
+            }
+        }
+        Ok(permit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(per_principal: RateLimit, per_session: RateLimit) -> RateLimits {
+        RateLimits {
+            per_principal,
+            per_session,
+        }
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let limit = RateLimit {
+            requests_per_second: Some(2.0),
+            burst: Some(3),
+            max_in_flight: None,
+        };
+        let mut bucket = TokenBucket::new(&limit).unwrap();
+        let start = bucket.updated;
+
+        for _ in 0..3 {
+            assert!(bucket.try_acquire(start).is_ok());
+        }
+        assert_eq!(bucket.try_acquire(start), Err(Duration::from_millis(500)));
+        assert!(bucket
+            .try_acquire(start + Duration::from_millis(500))
+            .is_ok());
+    }
+
+    #[test]
+    fn principal_rate_is_shared_across_sessions() {
+        let per_principal = RateLimit {
+            requests_per_second: Some(1.0),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits(per_principal, RateLimit::default()));
+        let (first, second) = (limiter.session(), limiter.session());
+
+        assert!(limiter.check_rate(Some("alice"), &first).is_ok());
+        assert!(matches!(
+            limiter.check_rate(Some("alice"), &second),
+            Err(Limited::Rate(_))
+        ));
+        assert!(limiter.check_rate(Some("bob"), &second).is_ok());
+        assert!(limiter.check_rate(None, &second).is_ok());
+    }
+
+    #[test]
+    fn idle_principals_are_evicted() {
+        let per_principal = RateLimit {
+            requests_per_second: Some(1.0),
+            max_in_flight: Some(1),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits(per_principal, RateLimit::default()));
+        let session = limiter.session();
+        assert!(limiter.check_rate(Some("alice"), &session).is_ok());
+        let _permit = limiter.acquire(Some("bob"), &session).unwrap();
+        assert!(limiter.check_rate(Some("carol"), &session).is_ok());
+
+        let mut principals = limiter.principals.lock().unwrap();
+        let later = principals.swept + SWEEP_INTERVAL;
+        principals.get("carol", per_principal, later);
+        // Alice's bucket has refilled, Bob still has a request in flight and
+        // Carol is the principal being looked up.
+        let mut names: Vec<_> = principals.states.keys().map(|k| k.to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["bob", "carol"]);
+    }
+
+    #[test]
+    fn permits_release_in_flight_slots() {
+        let per_session = RateLimit {
+            max_in_flight: Some(1),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(limits(RateLimit::default(), per_session));
+        let session = limiter.session();
+
+        let permit = limiter.acquire(Some("alice"), &session).unwrap();
+        assert!(matches!(
+            limiter.acquire(Some("alice"), &session),
+            Err(Limited::InFlight)
+        ));
+        drop(permit);
+        assert!(limiter.acquire(Some("alice"), &session).is_ok());
+    }
+
+    #[test]
+    fn new_limits_apply_to_open_sessions() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let session = limiter.session();
+        let permit = limiter.acquire(None, &session).unwrap();
+
+        limiter.set_limits(limits(
+            RateLimit::default(),
+            RateLimit {
+                max_in_flight: Some(1),
+                ..Default::default()
+            },
+        ));
+        assert!(matches!(
+            limiter.acquire(None, &session),
+            Err(Limited::InFlight)
+        ));
+        drop(permit);
+        assert!(limiter.acquire(None, &session).is_ok());
+    }
+}
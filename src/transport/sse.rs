@@ -2,30 +2,230 @@
 //!
 //! This reimplements rmcp's SSE server logic to allow wrapping with auth middleware.
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
 
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, Extensions, HeaderMap, StatusCode},
     response::{
         sse::{Event, Sse},
-        Response,
+        IntoResponse, Response,
     },
     routing::{get, post},
     Json, Router,
 };
 use futures::{Sink, SinkExt, Stream, StreamExt};
 use rmcp::{
-    model::ClientJsonRpcMessage,
+    model::{ClientJsonRpcMessage, RequestId, ServerNotification},
     service::{RxJsonRpcMessage, TxJsonRpcMessage},
     RoleServer,
 };
+use std::sync::Mutex;
 use tokio::sync::{mpsc, RwLock};
 use tokio_stream::wrappers::ReceiverStream;
-use tokio_util::sync::PollSender;
+use tokio_util::sync::{CancellationToken, PollSender};
+use tracing::Instrument;
+
+use super::message::{
+    cancelled_request, duplicate_request, empty_result, forbidden, request_info, required_scope,
+    resource_list_changed, resource_updated, response_id, subscription, too_many_requests,
+    too_many_subscriptions, unknown_resource, Subscription,
+};
+use super::rate_limit::{Limited, Permit, RateLimiter, SessionLimiter};
+#[cfg(feature = "audit")]
+use crate::audit::{redact, AuditEvent, AuditEventKind, AuditSink};
+use crate::auth::{remote_ip, Principal};
+use crate::config::RateLimits;
+use crate::timestamp::format_rfc3339;
 
 type SessionId = Arc<str>;
-type TxStore = Arc<RwLock<HashMap<SessionId, mpsc::Sender<ClientJsonRpcMessage>>>>;
+type TxStore = Arc<RwLock<HashMap<SessionId, Session>>>;
+/// In-flight slots of requests awaiting a response, by request id.
+type Pending = Arc<Mutex<HashMap<RequestId, PendingRequest>>>;
+
+/// How long a request may stay unanswered by default before its in-flight
+/// slot is released.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Predicate for [`SseServerConfig::subscribable`].
+pub type ResourceFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Default [`SseServerConfig::max_subscriptions`].
+const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+
+/// A request forwarded to the MCP service and not yet answered.
+struct PendingRequest {
+    _permit: Permit,
+    /// Distinguishes requests reusing the id of an earlier one.
+    seq: u64,
+    /// Task releasing the permit when the request times out.
+    expiry: tokio::task::AbortHandle,
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        self.expiry.abort();
+    }
+}
+
+/// Entry holding `permit` for request `id` in `pending` until the request
+/// is answered or cancelled, or `timeout` passes.
+fn track_pending(
+    pending: &Pending,
+    id: RequestId,
+    permit: Permit,
+    timeout: Duration,
+) -> PendingRequest {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+    let expiry = {
+        let pending = Arc::downgrade(pending);
+        let id = id.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let Some(pending) = pending.upgrade() else {
+                return;
+            };
+            let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+            if pending.get(&id).is_some_and(|request| request.seq == seq) {
+                tracing::warn!(%id, "request unanswered after timeout, releasing its in-flight slot");
+                pending.remove(&id);
+            }
+        })
+        .abort_handle()
+    };
+    PendingRequest {
+        _permit: permit,
+        seq,
+        expiry,
+    }
+}
+
+/// Options for [`AuthSseServer::with_config`].
+#[derive(Clone, Default)]
+pub struct SseServerConfig {
+    /// Check `tools/call`, `resources/read`, `resources/subscribe` and
+    /// `prompts/get` requests against the scopes of the session's
+    /// [`Principal`].
+    ///
+    /// Calls need the `tool:<name>`, `resource:<uri>` and `prompt:<name>`
+    /// scope respectively (wildcards such as `tool:*` apply). Requests the
+    /// principal lacks the scope for are answered with a JSON-RPC error and
+    /// never reach the MCP service. Sessions without a principal, such as
+    /// clients let in by
+    /// [`trust_networks`](crate::TokenAuthLayer::trust_networks) or a public
+    /// path, have no scopes and may only send unscoped requests.
+    pub authorize_requests: bool,
+    /// Request rate and in-flight limits per principal and per session.
+    ///
+    /// Messages over the rate are refused with `429 Too Many Requests`, and
+    /// so are new `/sse` sessions over the principal's rate; requests over the in-flight limit, or reusing the id of a request in
+    /// flight, are answered with a JSON-RPC error.
+    pub rate_limits: RateLimits,
+    /// How long a request counts as in flight without a response
+    /// (default: 5 minutes).
+    ///
+    /// Requests the client cancels with `notifications/cancelled` are
+    /// released immediately.
+    pub request_timeout: Option<Duration>,
+    /// Answer `resources/subscribe` and `resources/unsubscribe` in the
+    /// transport and remember each session's subscriptions.
+    ///
+    /// The requests never reach the MCP service; a [`ResourceNotifier`]
+    /// delivers `notifications/resources/updated` to the subscribed sessions.
+    /// They count against the [rate limits](Self::rate_limits) like other
+    /// requests.
+    pub resource_subscriptions: bool,
+    /// Whether a URI may be subscribed to, e.g. `FileResources::serves`.
+    ///
+    /// Subscriptions to other URIs are answered with a "resource not found"
+    /// error. Without it, any URI is accepted.
+    pub subscribable: Option<ResourceFilter>,
+    /// Most resources a session may be subscribed to (default: 100).
+    pub max_subscriptions: Option<usize>,
+    /// Config published by [`watch_config`](crate::config::watch_config).
+    ///
+    /// When set, its current rate limits are used instead of
+    /// [`rate_limits`](Self::rate_limits), so reloaded limits apply to open
+    /// sessions.
+    #[cfg(feature = "reload")]
+    pub reload: Option<tokio::sync::watch::Receiver<Arc<crate::config::BaseConfig>>>,
+    /// Sink receiving session open/close and tool call events.
+    #[cfg(feature = "audit")]
+    pub audit: Option<Arc<dyn AuditSink>>,
+}
+
+/// Server-side state of an open SSE session.
+struct Session {
+    tx: mpsc::Sender<ClientJsonRpcMessage>,
+    to_client: mpsc::Sender<TxJsonRpcMessage<RoleServer>>,
+    span: tracing::Span,
+    principal: Option<Principal>,
+    remote_addr: Option<IpAddr>,
+    limiter: SessionLimiter,
+    pending: Pending,
+    /// URIs of subscribed resources.
+    subscriptions: Mutex<HashSet<String>>,
+    opened_at: SystemTime,
+    stats: Arc<SessionStats>,
+    /// Cancelled to end the event stream when the session is closed by
+    /// [`Sessions::close`].
+    closed: CancellationToken,
+}
+
+/// Message counters of a session.
+#[derive(Default)]
+struct SessionStats {
+    received: AtomicU64,
+    sent: AtomicU64,
+}
+
+impl Session {
+    fn info(&self, id: &SessionId) -> SessionInfo {
+        SessionInfo {
+            id: id.to_string(),
+            principal: self.principal.as_ref().map(|p| p.name().to_string()),
+            remote_addr: self.remote_addr,
+            opened_at: format_rfc3339(self.opened_at),
+            messages_received: self.stats.received.load(Ordering::Relaxed),
+            messages_sent: self.stats.sent.load(Ordering::Relaxed),
+            subscriptions: self
+                .subscriptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .len(),
+        }
+    }
+
+    #[cfg(feature = "audit")]
+    fn audit_event(&self, session_id: &str, kind: AuditEventKind) -> AuditEvent {
+        session_audit_event(kind, session_id, self.principal.as_ref(), self.remote_addr)
+    }
+}
+
+#[cfg(feature = "audit")]
+fn session_audit_event(
+    kind: AuditEventKind,
+    session_id: &str,
+    principal: Option<&Principal>,
+    remote_addr: Option<IpAddr>,
+) -> AuditEvent {
+    let mut event = AuditEvent::new(kind)
+        .session_id(session_id)
+        .remote_addr(remote_addr);
+    if let Some(principal) = principal {
+        event = event.principal(principal.name());
+    }
+    event
+}
 
 /// Shared application state for SSE server
 #[derive(Clone)]
@@ -33,6 +233,8 @@ struct SseApp {
     txs: TxStore,
     transport_tx: mpsc::UnboundedSender<SseTransport>,
     post_path: Arc<str>,
+    config: Arc<SseServerConfig>,
+    limiter: Arc<RateLimiter>,
 }
 
 /// Transport for a single SSE session.
@@ -44,6 +246,21 @@ pub struct SseTransport {
     sink: PollSender<TxJsonRpcMessage<RoleServer>>,
     session_id: SessionId,
     tx_store: TxStore,
+    pending: Pending,
+    span: tracing::Span,
+    #[cfg(feature = "audit")]
+    audit: Option<Arc<dyn AuditSink>>,
+}
+
+impl SseTransport {
+    /// The span covering this session.
+    ///
+    /// Instrument the MCP service serving this transport with it so that all
+    /// of its events are attributed to the session (and, with the `otel`
+    /// feature, to the trace of the client's `traceparent` header).
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
 }
 
 impl Sink<TxJsonRpcMessage<RoleServer>> for SseTransport {
@@ -62,6 +279,12 @@ impl Sink<TxJsonRpcMessage<RoleServer>> for SseTransport {
         mut self: std::pin::Pin<&mut Self>,
         item: TxJsonRpcMessage<RoleServer>,
     ) -> Result<(), Self::Error> {
+        if let Some(id) = response_id(&item) {
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(id);
+        }
         self.sink
             .start_send_unpin(item)
             .map_err(std::io::Error::other)
@@ -88,8 +311,16 @@ impl Sink<TxJsonRpcMessage<RoleServer>> for SseTransport {
         if result.is_ready() {
             let session_id = self.session_id.clone();
             let tx_store = self.tx_store.clone();
+            #[cfg(feature = "audit")]
+            let audit = self.audit.clone();
             tokio::spawn(async move {
-                tx_store.write().await.remove(&session_id);
+                let session = tx_store.write().await.remove(&session_id);
+                #[cfg(feature = "audit")]
+                if let (Some(audit), Some(session)) = (audit, session) {
+                    audit.record(&session.audit_event(&session_id, AuditEventKind::SessionClosed));
+                }
+                #[cfg(not(feature = "audit"))]
+                drop(session);
             });
         }
         result
@@ -123,42 +354,312 @@ struct PostEventQuery {
     session_id: String,
 }
 
+/// Continue the trace of the client's `traceparent` header, if any.
+#[cfg(feature = "otel")]
+fn link_remote_parent(span: &tracing::Span, headers: &HeaderMap) {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl opentelemetry::propagation::Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|key| key.as_str()).collect()
+        }
+    }
+
+    let cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(headers))
+    });
+    if cx.span().span_context().is_valid() {
+        let _ = span.set_parent(cx);
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+fn link_remote_parent(_span: &tracing::Span, _headers: &HeaderMap) {}
+
+/// Audit event of a `tools/call` request, built while the session store is
+/// locked and recorded with [`record_tool_call`] once it is released.
+#[cfg(feature = "audit")]
+fn tool_call_event(
+    app: &SseApp,
+    session: &Session,
+    session_id: &str,
+    message: &ClientJsonRpcMessage,
+) -> Option<AuditEvent> {
+    use rmcp::model::ClientRequest;
+
+    let (Some(_), ClientJsonRpcMessage::Request(request)) = (&app.config.audit, message) else {
+        return None;
+    };
+    let ClientRequest::CallToolRequest(call) = &request.request else {
+        return None;
+    };
+    let kind = AuditEventKind::ToolCalled {
+        tool: call.params.name.to_string(),
+        arguments: call
+            .params
+            .arguments
+            .clone()
+            .map(|arguments| redact(arguments.into())),
+    };
+    Some(session.audit_event(session_id, kind))
+}
+
+#[cfg(not(feature = "audit"))]
+fn tool_call_event(
+    _app: &SseApp,
+    _session: &Session,
+    _session_id: &str,
+    _message: &ClientJsonRpcMessage,
+) {
+}
+
+/// Record the event of [`tool_call_event`] to the audit sink.
+#[cfg(feature = "audit")]
+fn record_tool_call(app: &SseApp, event: Option<AuditEvent>) {
+    if let (Some(audit), Some(event)) = (&app.config.audit, event) {
+        audit.record(&event);
+    }
+}
+
+#[cfg(not(feature = "audit"))]
+fn record_tool_call(_app: &SseApp, _event: ()) {}
+
+/// Apply a `resources/subscribe` or `resources/unsubscribe` request to the
+/// session and return the reply.
+fn update_subscriptions(
+    config: &SseServerConfig,
+    session: &Session,
+    message: &ClientJsonRpcMessage,
+) -> Option<TxJsonRpcMessage<RoleServer>> {
+    let (id, change) = subscription(message)?;
+    let mut subscriptions = session
+        .subscriptions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    match change {
+        Subscription::Subscribe(uri) => {
+            if let Some(serves) = &config.subscribable {
+                if !serves(uri) {
+                    tracing::debug!(uri, "subscription to unknown resource rejected");
+                    return Some(unknown_resource(id.clone(), uri));
+                }
+            }
+            let max = config
+                .max_subscriptions
+                .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS);
+            if !subscriptions.contains(uri) && subscriptions.len() >= max {
+                tracing::warn!(uri, max, "subscription limit reached");
+                return Some(too_many_subscriptions(id.clone(), max));
+            }
+            tracing::debug!(uri, "resource subscribed");
+            subscriptions.insert(uri.to_string());
+        }
+        Subscription::Unsubscribe(uri) => {
+            tracing::debug!(uri, "resource unsubscribed");
+            subscriptions.remove(uri);
+        }
+    }
+    Some(empty_result(id.clone()))
+}
+
 async fn post_event_handler(
     State(app): State<SseApp>,
     Query(PostEventQuery { session_id }): Query<PostEventQuery>,
+    headers: HeaderMap,
+    extensions: Extensions,
     Json(message): Json<ClientJsonRpcMessage>,
-) -> Result<StatusCode, StatusCode> {
-    tracing::debug!(session_id, ?message, "received client message");
-
-    let tx = {
+) -> Result<StatusCode, Response> {
+    #[cfg(feature = "reload")]
+    if let Some(config) = &app.config.reload {
+        app.limiter.set_limits(config.borrow().rate_limits);
+    }
+    let (tx, to_client, reply, session_span, tool_call) = {
         let store = app.txs.read().await;
-        store
+        let session = store
             .get(session_id.as_str())
-            .ok_or(StatusCode::NOT_FOUND)?
-            .clone()
+            .ok_or(StatusCode::NOT_FOUND.into_response())?;
+        // Only the principal that opened the session may post to it.
+        let sender = extensions.get::<Principal>().map(Principal::name);
+        if session.principal.is_some() && session.principal.as_ref().map(Principal::name) != sender
+        {
+            tracing::warn!(session_id, "message from a different principal rejected");
+            return Err(StatusCode::FORBIDDEN.into_response());
+        }
+        let principal = session.principal.as_ref().map(Principal::name);
+        if let Err(Limited::Rate(retry_after)) = app.limiter.check_rate(principal, &session.limiter)
+        {
+            tracing::warn!(session_id, ?principal, "request rate limit exceeded");
+            return Err(rate_limited(retry_after));
+        }
+        session.stats.received.fetch_add(1, Ordering::Relaxed);
+        if let Some(id) = cancelled_request(&message) {
+            session
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(id);
+        }
+        let reply = match (&session.principal, required_scope(&message)) {
+            // Sessions without a principal (trusted networks, public paths)
+            // have no scopes.
+            (principal, Some((id, scope)))
+                if app.config.authorize_requests
+                    && !principal.as_ref().is_some_and(|p| p.has_scope(&scope)) =>
+            {
+                tracing::warn!(session_id, %scope, "request outside the principal's scopes rejected");
+                Some(forbidden(id.clone(), &scope))
+            }
+            _ if app.config.resource_subscriptions && subscription(&message).is_some() => {
+                // Answered here, so the permit is released right away.
+                match app.limiter.acquire(principal, &session.limiter) {
+                    Ok(_permit) => update_subscriptions(&app.config, session, &message),
+                    Err(_) => {
+                        tracing::warn!(session_id, ?principal, "in-flight request limit exceeded");
+                        request_info(&message).map(|(_, id)| too_many_requests(id.clone()))
+                    }
+                }
+            }
+            _ => request_info(&message).and_then(|(_, id)| {
+                // Checked before taking a permit, so reusing an id cannot
+                // replace an entry and release its permit.
+                let mut pending = session.pending.lock().unwrap_or_else(|e| e.into_inner());
+                if pending.contains_key(id) {
+                    tracing::warn!(session_id, %id, "request reusing an id in flight rejected");
+                    return Some(duplicate_request(id.clone()));
+                }
+                match app.limiter.acquire(principal, &session.limiter) {
+                    Ok(permit) => {
+                        let timeout = app
+                            .config
+                            .request_timeout
+                            .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+                        let request = track_pending(&session.pending, id.clone(), permit, timeout);
+                        pending.insert(id.clone(), request);
+                        None
+                    }
+                    Err(_) => {
+                        tracing::warn!(session_id, ?principal, "in-flight request limit exceeded");
+                        Some(too_many_requests(id.clone()))
+                    }
+                }
+            }),
+        };
+        (
+            session.tx.clone(),
+            session.to_client.clone(),
+            reply,
+            session.span.clone(),
+            tool_call_event(&app, session, &session_id, &message),
+        )
+    };
+    // Recorded after releasing the store, so a slow sink does not hold up
+    // other sessions.
+    if reply.is_none() {
+        record_tool_call(&app, tool_call);
+    }
+
+    let span = match request_info(&message) {
+        Some((method, id)) => tracing::info_span!(
+            parent: &session_span,
+            "mcp_request",
+            session_id,
+            method,
+            id = %id,
+        ),
+        None => tracing::info_span!(parent: &session_span, "mcp_message", session_id),
     };
+    link_remote_parent(&span, &headers);
+
+    async move {
+        tracing::debug!(?message, "received client message");
+
+        if let Some(reply) = reply {
+            if to_client.send(reply).await.is_err() {
+                return Err(StatusCode::GONE.into_response());
+            }
+            return Ok(StatusCode::ACCEPTED);
+        }
 
-    if tx.send(message).await.is_err() {
-        tracing::error!("failed to send message to session");
-        return Err(StatusCode::GONE);
+        if tx.send(message).await.is_err() {
+            tracing::error!("failed to send message to session");
+            return Err(StatusCode::GONE.into_response());
+        }
+
+        Ok(StatusCode::ACCEPTED)
     }
+    .instrument(span)
+    .await
+}
 
-    Ok(StatusCode::ACCEPTED)
+/// `429 Too Many Requests` asking the client to retry after `retry_after`.
+fn rate_limited(retry_after: Duration) -> Response {
+    let retry_after = retry_after.as_secs_f64().ceil().max(1.0).to_string();
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after)],
+    )
+        .into_response()
 }
 
 async fn sse_handler(
     State(app): State<SseApp>,
-) -> Result<Sse<impl Stream<Item = Result<Event, std::io::Error>>>, Response<String>> {
+    headers: HeaderMap,
+    extensions: Extensions,
+) -> Result<Sse<impl Stream<Item = Result<Event, std::io::Error>>>, Response> {
+    let principal = extensions.get::<Principal>().cloned();
+    // Opening a session counts against the principal's request rate, so a
+    // client cannot dodge it by reconnecting.
+    if let Some(name) = principal.as_ref().map(Principal::name) {
+        #[cfg(feature = "reload")]
+        if let Some(config) = &app.config.reload {
+            app.limiter.set_limits(config.borrow().rate_limits);
+        }
+        if let Err(Limited::Rate(retry_after)) = app.limiter.check_principal_rate(name) {
+            tracing::warn!(principal = name, "session rate limit exceeded");
+            return Err(rate_limited(retry_after));
+        }
+    }
     let session_id = generate_session_id();
-    tracing::info!(%session_id, "new SSE connection");
+    let remote_addr = remote_ip(&extensions);
+    let span = tracing::info_span!(
+        "mcp_session",
+        %session_id,
+        principal = principal.as_ref().map(Principal::name),
+    );
+    link_remote_parent(&span, &headers);
+    tracing::info!(parent: &span, %session_id, ?remote_addr, "new SSE connection");
 
     let (from_client_tx, from_client_rx) = mpsc::channel(64);
     let (to_client_tx, to_client_rx) = mpsc::channel(64);
 
-    app.txs
-        .write()
-        .await
-        .insert(session_id.clone(), from_client_tx);
+    let session = Session {
+        tx: from_client_tx,
+        to_client: to_client_tx.clone(),
+        span: span.clone(),
+        principal,
+        remote_addr,
+        limiter: app.limiter.session(),
+        pending: Pending::default(),
+        subscriptions: Mutex::default(),
+        opened_at: SystemTime::now(),
+        stats: Arc::default(),
+        closed: CancellationToken::new(),
+    };
+    #[cfg(feature = "audit")]
+    if let Some(audit) = &app.config.audit {
+        audit.record(&session.audit_event(&session_id, AuditEventKind::SessionOpened));
+    }
+    let session_pending = session.pending.clone();
+    let stats = session.stats.clone();
+    let closed = session.closed.clone();
+    app.txs.write().await.insert(session_id.clone(), session);
 
     let stream = ReceiverStream::new(from_client_rx);
     let sink = PollSender::new(to_client_tx);
@@ -168,13 +669,15 @@ async fn sse_handler(
         sink,
         session_id: session_id.clone(),
         tx_store: app.txs.clone(),
+        pending: session_pending,
+        span,
+        #[cfg(feature = "audit")]
+        audit: app.config.audit.clone(),
     };
 
     if app.transport_tx.send(transport).is_err() {
         tracing::warn!("failed to send transport - server may be closing");
-        let mut response = Response::new("server is closing".to_string());
-        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
-        return Err(response);
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, "server is closing").into_response());
     }
 
     let post_path = app.post_path.as_ref();
@@ -182,13 +685,17 @@ async fn sse_handler(
         .event("endpoint")
         .data(format!("{post_path}?sessionId={session_id}"));
 
-    let message_stream =
-        ReceiverStream::new(to_client_rx).map(|message| match serde_json::to_string(&message) {
+    let message_stream = ReceiverStream::new(to_client_rx).map(move |message| {
+        stats.sent.fetch_add(1, Ordering::Relaxed);
+        match serde_json::to_string(&message) {
             Ok(json) => Ok(Event::default().event("message").data(&json)),
             Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
-        });
+        }
+    });
 
-    let stream = futures::stream::once(futures::future::ok(endpoint_event)).chain(message_stream);
+    let stream = futures::stream::once(futures::future::ok(endpoint_event))
+        .chain(message_stream)
+        .take_until(closed.cancelled_owned());
 
     Ok(Sse::new(stream)
         .keep_alive(axum::response::sse::KeepAlive::new().interval(Duration::from_secs(30))))
@@ -208,11 +715,15 @@ async fn sse_handler(
 ///
 /// // Accept connections in a loop
 /// while let Some(transport) = sse_server.next_transport().await {
-///     // Handle the transport...
+///     let span = transport.span().clone();
+///     tokio::spawn(service.clone().serve(transport).instrument(span));
 /// }
 /// ```
 pub struct AuthSseServer {
     transport_rx: mpsc::UnboundedReceiver<SseTransport>,
+    txs: TxStore,
+    #[cfg_attr(not(feature = "audit"), allow(dead_code))]
+    config: Arc<SseServerConfig>,
 }
 
 impl AuthSseServer {
@@ -222,20 +733,55 @@ impl AuthSseServer {
     /// - `server` is used to accept new transports via `next_transport()`
     /// - `router` contains the SSE endpoints and can be layered with middleware
     pub fn new() -> (Self, Router) {
+        Self::with_config(SseServerConfig::default())
+    }
+
+    /// Create a new SSE server with the given options.
+    ///
+    /// See [`new`](Self::new) for the returned tuple.
+    pub fn with_config(config: SseServerConfig) -> (Self, Router) {
         let (transport_tx, transport_rx) = mpsc::unbounded_channel();
 
+        let txs = TxStore::default();
         let app = SseApp {
-            txs: Arc::new(RwLock::new(HashMap::new())),
+            txs: txs.clone(),
             transport_tx,
             post_path: Arc::from("/message"),
+            limiter: Arc::new(RateLimiter::new(config.rate_limits)),
+            config: Arc::new(config),
         };
+        let config = app.config.clone();
 
         let router = Router::new()
             .route("/sse", get(sse_handler))
             .route("/message", post(post_event_handler))
             .with_state(app);
 
-        (Self { transport_rx }, router)
+        (
+            Self {
+                transport_rx,
+                txs,
+                config,
+            },
+            router,
+        )
+    }
+
+    /// Handle for listing and closing the open sessions of this server.
+    pub fn sessions(&self) -> Sessions {
+        Sessions {
+            txs: self.txs.clone(),
+            #[cfg(feature = "audit")]
+            audit: self.config.audit.clone(),
+        }
+    }
+
+    /// Handle for sending resource change notifications to the sessions of
+    /// this server.
+    pub fn resource_notifier(&self) -> ResourceNotifier {
+        ResourceNotifier {
+            txs: self.txs.clone(),
+        }
     }
 
     /// Wait for the next transport (new SSE connection).
@@ -251,3 +797,613 @@ impl Default for AuthSseServer {
         Self::new().0
     }
 }
+
+/// Lists and closes the open SSE sessions of an [`AuthSseServer`].
+///
+/// Obtained from [`AuthSseServer::sessions`], e.g. for an admin UI.
+#[derive(Clone)]
+pub struct Sessions {
+    txs: TxStore,
+    #[cfg(feature = "audit")]
+    audit: Option<Arc<dyn AuditSink>>,
+}
+
+/// Snapshot of an open SSE session.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SessionInfo {
+    /// Session id, as in the message endpoint's `sessionId` parameter.
+    pub id: String,
+    /// Name of the principal that opened the session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// Client address, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<IpAddr>,
+    /// RFC 3339 timestamp (UTC) of when the session was opened.
+    pub opened_at: String,
+    /// Messages posted by the client.
+    pub messages_received: u64,
+    /// Messages sent to the client.
+    pub messages_sent: u64,
+    /// Number of subscribed resources.
+    pub subscriptions: usize,
+}
+
+impl Sessions {
+    /// The open sessions, oldest first.
+    pub async fn list(&self) -> Vec<SessionInfo> {
+        let store = self.txs.read().await;
+        let mut sessions: Vec<_> = store.iter().collect();
+        sessions.sort_by_key(|(_, session)| session.opened_at);
+        sessions
+            .into_iter()
+            .map(|(id, session)| session.info(id))
+            .collect()
+    }
+
+    /// The open session `session_id`, if any.
+    pub async fn get(&self, session_id: &str) -> Option<SessionInfo> {
+        let store = self.txs.read().await;
+        store
+            .get_key_value(session_id)
+            .map(|(id, session)| session.info(id))
+    }
+
+    /// Close the session `session_id` on behalf of `operator`, returning
+    /// whether it was open.
+    ///
+    /// Ends the client's event stream and the stream of its
+    /// [`SseTransport`], which stops the MCP service serving it. The audit
+    /// event names both the session's principal and the operator.
+    pub async fn close(&self, session_id: &str, operator: Option<&Principal>) -> bool {
+        let Some(session) = self.txs.write().await.remove(session_id) else {
+            return false;
+        };
+        session.closed.cancel();
+        let operator = operator.map(Principal::name);
+        tracing::info!(parent: &session.span, session_id, operator, "session closed by operator");
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &self.audit {
+            let kind = AuditEventKind::SessionClosedByOperator {
+                operator: operator.map(str::to_string),
+            };
+            audit.record(&session.audit_event(session_id, kind));
+        }
+        true
+    }
+
+    /// Send `notification` on behalf of `operator` to the sessions in
+    /// `session_ids`, or to all sessions if `None`, returning how many were
+    /// notified.
+    ///
+    /// Unknown session ids are skipped, and the notification is dropped for
+    /// sessions whose outgoing queue is full.
+    pub async fn notify(
+        &self,
+        notification: ServerNotification,
+        session_ids: Option<&[String]>,
+        operator: Option<&Principal>,
+    ) -> usize {
+        let method = super::message::notification_method(&notification);
+        let sent = send_to(
+            &self.txs,
+            super::message::notification(notification),
+            |id, _| match session_ids {
+                Some(ids) => ids.iter().any(|selected| **selected == **id),
+                None => true,
+            },
+        )
+        .await;
+        let operator = operator.map(Principal::name);
+        tracing::info!(method, sent, operator, "notification sent by operator");
+        #[cfg(feature = "audit")]
+        if let Some(audit) = &self.audit {
+            let mut event = AuditEvent::new(AuditEventKind::NotificationSent {
+                method: method.to_string(),
+                sessions: sent,
+            });
+            if let Some(operator) = operator {
+                event = event.principal(operator);
+            }
+            audit.record(&event);
+        }
+        sent
+    }
+}
+
+/// Sends resource change notifications to open SSE sessions.
+///
+/// Obtained from [`AuthSseServer::resource_notifier`]. Subscriptions are only
+/// tracked with [`SseServerConfig::resource_subscriptions`]. Notifications are
+/// dropped for sessions whose outgoing queue is full.
+#[derive(Clone)]
+pub struct ResourceNotifier {
+    txs: TxStore,
+}
+
+impl ResourceNotifier {
+    /// Send `notifications/resources/updated` to the sessions subscribed to
+    /// `uri`, returning how many were notified.
+    pub async fn resource_updated(&self, uri: &str) -> usize {
+        self.send(resource_updated(uri), |session| {
+            session
+                .subscriptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .contains(uri)
+        })
+        .await
+    }
+
+    /// Send `notifications/resources/list_changed` to all sessions,
+    /// returning how many were notified.
+    pub async fn resource_list_changed(&self) -> usize {
+        self.send(resource_list_changed(), |_| true).await
+    }
+
+    async fn send(
+        &self,
+        message: TxJsonRpcMessage<RoleServer>,
+        filter: impl Fn(&Session) -> bool,
+    ) -> usize {
+        send_to(&self.txs, message, |_, session| filter(session)).await
+    }
+}
+
+/// Send `message` to the sessions matching `filter`, returning how many it
+/// was queued for.
+async fn send_to(
+    txs: &TxStore,
+    message: TxJsonRpcMessage<RoleServer>,
+    filter: impl Fn(&SessionId, &Session) -> bool,
+) -> usize {
+    let store = txs.read().await;
+    let mut sent = 0;
+    for (session_id, session) in store.iter().filter(|(id, session)| filter(id, session)) {
+        match session.to_client.try_send(message.clone()) {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                tracing::debug!(%session_id, error = %e, "notification dropped");
+            }
+        }
+    }
+    sent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::util::ServiceExt;
+
+    /// Open an SSE session and return its event stream and message endpoint.
+    async fn open_session(
+        router: &Router,
+        principal: Option<&Principal>,
+    ) -> (axum::body::BodyDataStream, String) {
+        let mut request = Request::builder().uri("/sse").body(Body::empty()).unwrap();
+        if let Some(principal) = principal {
+            request.extensions_mut().insert(principal.clone());
+        }
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut events = response.into_body().into_data_stream();
+        let chunk = events.next().await.unwrap().unwrap();
+        let endpoint = std::str::from_utf8(&chunk)
+            .unwrap()
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap()
+            .to_string();
+        (events, endpoint)
+    }
+
+    async fn post_message(
+        router: &Router,
+        endpoint: &str,
+        principal: Option<&Principal>,
+        json: &str,
+    ) -> StatusCode {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri(endpoint)
+            .header("Content-Type", "application/json")
+            .body(Body::from(json.to_string()))
+            .unwrap();
+        if let Some(principal) = principal {
+            request.extensions_mut().insert(principal.clone());
+        }
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    /// Wait for the next message event and parse its data.
+    async fn next_message(events: &mut axum::body::BodyDataStream) -> serde_json::Value {
+        let chunk = events.next().await.unwrap().unwrap();
+        let data = std::str::from_utf8(&chunk)
+            .unwrap()
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap()
+            .to_string();
+        serde_json::from_str(&data).unwrap()
+    }
+
+    const PING: &str = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+
+    #[tokio::test]
+    async fn delivers_messages_to_transport() {
+        let (mut server, router) = AuthSseServer::new();
+        let (_events, endpoint) = open_session(&router, None).await;
+        let mut transport = server.next_transport().await.unwrap();
+
+        let status = post_message(&router, &endpoint, None, PING).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let message = transport.next().await.unwrap();
+        assert_eq!(request_info(&message).unwrap().0, "ping");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_session() {
+        let (_server, router) = AuthSseServer::new();
+        let status = post_message(&router, "/message?sessionId=nope", None, PING).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn rejects_messages_from_other_principal() {
+        let (_server, router) = AuthSseServer::new();
+        let alice = Principal::new("alice");
+        let (_events, endpoint) = open_session(&router, Some(&alice)).await;
+
+        let bob = Principal::new("bob");
+        let status = post_message(&router, &endpoint, Some(&bob), PING).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let status = post_message(&router, &endpoint, Some(&alice), PING).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn rejects_calls_outside_scopes() {
+        let config = SseServerConfig {
+            authorize_requests: true,
+            ..Default::default()
+        };
+        let (mut server, router) = AuthSseServer::with_config(config);
+        let alice = Principal::new("alice").with_scopes(["tool:search", "prompt:*"]);
+        let (mut events, endpoint) = open_session(&router, Some(&alice)).await;
+        let mut transport = server.next_transport().await.unwrap();
+
+        let denied = r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"delete"}}"#;
+        let status = post_message(&router, &endpoint, Some(&alice), denied).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let error = next_message(&mut events).await;
+        assert_eq!(error["id"], 5);
+        assert_eq!(error["error"]["data"]["requiredScope"], "tool:delete");
+
+        let allowed =
+            r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"search"}}"#;
+        let status = post_message(&router, &endpoint, Some(&alice), allowed).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let prompt = r#"{"jsonrpc":"2.0","id":7,"method":"prompts/get","params":{"name":"hi"}}"#;
+        post_message(&router, &endpoint, Some(&alice), prompt).await;
+
+        let ids: Vec<String> = [transport.next().await, transport.next().await]
+            .into_iter()
+            .map(|message| request_info(&message.unwrap()).unwrap().1.to_string())
+            .collect();
+        assert_eq!(ids, ["6", "7"]);
+
+        // Sessions without a principal get no scoped requests through.
+        let (mut events, endpoint) = open_session(&router, None).await;
+        let status = post_message(&router, &endpoint, None, allowed).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let error = next_message(&mut events).await;
+        assert_eq!(error["error"]["data"]["requiredScope"], "tool:search");
+    }
+
+    #[tokio::test]
+    async fn limits_request_rate_and_in_flight_requests() {
+        use crate::config::RateLimit;
+        use rmcp::model::{EmptyResult, JsonRpcResponse, JsonRpcVersion2_0, ServerResult};
+
+        let config = SseServerConfig {
+            rate_limits: RateLimits {
+                per_principal: RateLimit {
+                    requests_per_second: Some(0.001),
+                    // One token goes to opening the session.
+                    burst: Some(4),
+                    max_in_flight: None,
+                },
+                per_session: RateLimit {
+                    max_in_flight: Some(1),
+                    ..Default::default()
+                },
+            },
+            ..Default::default()
+        };
+        let (mut server, router) = AuthSseServer::with_config(config);
+        let alice = Principal::new("alice");
+        let (mut events, endpoint) = open_session(&router, Some(&alice)).await;
+        let mut transport = server.next_transport().await.unwrap();
+
+        let ping = |id: u32| format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"ping"}}"#);
+        let status = post_message(&router, &endpoint, Some(&alice), &ping(1)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let status = post_message(&router, &endpoint, Some(&alice), &ping(2)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let chunk = events.next().await.unwrap().unwrap();
+        assert!(std::str::from_utf8(&chunk).unwrap().contains("-32000"));
+
+        let request = transport.next().await.unwrap();
+        let (_, id) = request_info(&request).unwrap();
+        let response = TxJsonRpcMessage::<RoleServer>::Response(JsonRpcResponse {
+            jsonrpc: JsonRpcVersion2_0,
+            id: id.clone(),
+            result: ServerResult::EmptyResult(EmptyResult {}),
+        });
+        transport.send(response).await.unwrap();
+
+        let status = post_message(&router, &endpoint, Some(&alice), &ping(3)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(
+            request_info(&transport.next().await.unwrap()).unwrap().1,
+            &RequestId::Number(3)
+        );
+
+        let status = post_message(&router, &endpoint, Some(&alice), &ping(4)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn limits_session_rate_per_principal() {
+        use crate::config::RateLimit;
+
+        let config = SseServerConfig {
+            rate_limits: RateLimits {
+                per_principal: RateLimit {
+                    requests_per_second: Some(0.001),
+                    burst: Some(1),
+                    max_in_flight: None,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (_server, router) = AuthSseServer::with_config(config);
+        let alice = Principal::new("alice");
+        let _session = open_session(&router, Some(&alice)).await;
+
+        let mut request = Request::builder().uri("/sse").body(Body::empty()).unwrap();
+        request.extensions_mut().insert(alice);
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        let _session = open_session(&router, Some(&Principal::new("bob"))).await;
+    }
+
+    #[tokio::test]
+    async fn rejects_reused_request_ids() {
+        use rmcp::model::{EmptyResult, JsonRpcResponse, JsonRpcVersion2_0, ServerResult};
+
+        let config = SseServerConfig {
+            rate_limits: RateLimits {
+                per_session: crate::config::RateLimit {
+                    max_in_flight: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (mut server, router) = AuthSseServer::with_config(config);
+        let (mut events, endpoint) = open_session(&router, None).await;
+        let mut transport = server.next_transport().await.unwrap();
+        let ping = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+
+        let (first, second) = tokio::join!(
+            post_message(&router, &endpoint, None, ping),
+            post_message(&router, &endpoint, None, ping),
+        );
+        assert_eq!(
+            (first, second),
+            (StatusCode::ACCEPTED, StatusCode::ACCEPTED)
+        );
+        let request = transport.next().await.unwrap();
+        assert_eq!(request_info(&request).unwrap().1, &RequestId::Number(1));
+        let reply = next_message(&mut events).await;
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["error"]["code"], -32600);
+
+        // Answering the request frees its id.
+        let response = TxJsonRpcMessage::<RoleServer>::Response(JsonRpcResponse {
+            jsonrpc: JsonRpcVersion2_0,
+            id: RequestId::Number(1),
+            result: ServerResult::EmptyResult(EmptyResult {}),
+        });
+        transport.send(response).await.unwrap();
+        next_message(&mut events).await;
+        post_message(&router, &endpoint, None, ping).await;
+        let request = transport.next().await.unwrap();
+        assert_eq!(request_info(&request).unwrap().1, &RequestId::Number(1));
+    }
+
+    #[tokio::test]
+    async fn releases_cancelled_and_timed_out_requests() {
+        let config = SseServerConfig {
+            rate_limits: RateLimits {
+                per_session: crate::config::RateLimit {
+                    max_in_flight: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            request_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let (mut server, router) = AuthSseServer::with_config(config);
+        let (_events, endpoint) = open_session(&router, None).await;
+        let mut transport = server.next_transport().await.unwrap();
+        let ping = |id: u32| format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"ping"}}"#);
+        let cancel = r#"{"jsonrpc":"2.0","method":"notifications/cancelled",
+            "params":{"requestId":1}}"#;
+
+        for message in [ping(1), cancel.to_string(), ping(2)] {
+            post_message(&router, &endpoint, None, &message).await;
+        }
+        let ids: Vec<_> = [transport.next().await, transport.next().await]
+            .into_iter()
+            .map(|message| request_info(&message.unwrap()).map(|(_, id)| id.clone()))
+            .collect();
+        assert_eq!(ids, [Some(RequestId::Number(1)), None]);
+        // Request 1 was cancelled, so request 2 gets its slot.
+        let request = tokio::time::timeout(Duration::from_secs(1), transport.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(request_info(&request).unwrap().1, &RequestId::Number(2));
+
+        // Request 2 is never answered, and its slot is released after the
+        // timeout.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        post_message(&router, &endpoint, None, &ping(3)).await;
+        let request = transport.next().await.unwrap();
+        assert_eq!(request_info(&request).unwrap().1, &RequestId::Number(3));
+    }
+
+    #[tokio::test]
+    async fn notifies_subscribed_sessions() {
+        let config = SseServerConfig {
+            resource_subscriptions: true,
+            ..Default::default()
+        };
+        let (server, router) = AuthSseServer::with_config(config);
+        let notifier = server.resource_notifier();
+        let (mut subscribed, endpoint) = open_session(&router, None).await;
+        let (mut other, _) = open_session(&router, None).await;
+
+        let subscribe = r#"{"jsonrpc":"2.0","id":1,"method":"resources/subscribe",
+            "params":{"uri":"file:///data/a.md"}}"#;
+        let status = post_message(&router, &endpoint, None, subscribe).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        assert_eq!(next_message(&mut subscribed).await["id"], 1);
+
+        assert_eq!(notifier.resource_updated("file:///data/a.md").await, 1);
+        assert_eq!(notifier.resource_updated("file:///data/b.md").await, 0);
+        let update = next_message(&mut subscribed).await;
+        assert_eq!(update["method"], "notifications/resources/updated");
+        assert_eq!(update["params"]["uri"], "file:///data/a.md");
+
+        assert_eq!(notifier.resource_list_changed().await, 2);
+        let changed = next_message(&mut other).await;
+        assert_eq!(changed["method"], "notifications/resources/list_changed");
+    }
+
+    #[tokio::test]
+    async fn limits_subscriptions() {
+        let config = SseServerConfig {
+            resource_subscriptions: true,
+            subscribable: Some(Arc::new(|uri: &str| uri.starts_with("file:///data/"))),
+            max_subscriptions: Some(1),
+            rate_limits: RateLimits {
+                per_session: crate::config::RateLimit {
+                    max_in_flight: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (_server, router) = AuthSseServer::with_config(config);
+        let (mut events, endpoint) = open_session(&router, None).await;
+        let subscribe = |id: u32, uri: &str| {
+            format!(
+                r#"{{"jsonrpc":"2.0","id":{id},"method":"resources/subscribe",
+                    "params":{{"uri":"{uri}"}}}}"#
+            )
+        };
+
+        for (id, uri, error) in [
+            (1, "file:///etc/passwd", Some(-32002)),
+            (2, "file:///data/a.md", None),
+            (3, "file:///data/b.md", Some(-32600)),
+            (4, "file:///data/a.md", None),
+        ] {
+            post_message(&router, &endpoint, None, &subscribe(id, uri)).await;
+            let reply = next_message(&mut events).await;
+            assert_eq!(reply["id"], id);
+            assert_eq!(reply["error"]["code"].as_i64(), error, "{uri}");
+        }
+
+        // Subscriptions wait for a slot like other requests.
+        post_message(&router, &endpoint, None, PING).await;
+        post_message(&router, &endpoint, None, &subscribe(5, "file:///data/a.md")).await;
+        let reply = next_message(&mut events).await;
+        assert_eq!(reply["id"], 5);
+        assert_eq!(reply["error"]["code"], -32000);
+    }
+
+    #[tokio::test]
+    async fn lists_and_closes_sessions() {
+        let (mut server, router) = AuthSseServer::new();
+        let sessions = server.sessions();
+        let alice = Principal::new("alice");
+        let (mut events, endpoint) = open_session(&router, Some(&alice)).await;
+        let mut transport = server.next_transport().await.unwrap();
+
+        let status = post_message(&router, &endpoint, Some(&alice), PING).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let listed = sessions.list().await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].principal.as_deref(), Some("alice"));
+        assert_eq!(listed[0].messages_received, 1);
+        assert!(endpoint.ends_with(&listed[0].id));
+
+        assert!(sessions.close(&listed[0].id, None).await);
+        assert!(!sessions.close(&listed[0].id, None).await);
+        assert!(sessions.list().await.is_empty());
+        assert!(transport.next().await.is_some());
+        assert!(transport.next().await.is_none());
+        assert!(events.next().await.is_none());
+        let status = post_message(&router, &endpoint, Some(&alice), PING).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn audits_session_and_tool_calls() {
+        use crate::audit::MemoryAuditSink;
+
+        let sink = Arc::new(MemoryAuditSink::new());
+        let (mut server, router) = AuthSseServer::with_config(SseServerConfig {
+            audit: Some(sink.clone()),
+            ..Default::default()
+        });
+        let alice = Principal::new("alice");
+        let (_events, endpoint) = open_session(&router, Some(&alice)).await;
+        let mut transport = server.next_transport().await.unwrap();
+
+        let call = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call",
+            "params":{"name":"fetch","arguments":{"url":"https://x","api_key":"k"}}}"#;
+        let status = post_message(&router, &endpoint, Some(&alice), call).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        transport.close().await.unwrap();
+        tokio::task::yield_now().await;
+
+        let events = sink.events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, AuditEventKind::SessionOpened);
+        assert_eq!(events[0].principal.as_deref(), Some("alice"));
+        assert_eq!(
+            events[1].kind,
+            AuditEventKind::ToolCalled {
+                tool: "fetch".to_string(),
+                arguments: Some(serde_json::json!({"url": "https://x", "api_key": "[REDACTED]"})),
+            }
+        );
+        assert_eq!(events[1].session_id, events[0].session_id);
+        assert_eq!(events[2].kind, AuditEventKind::SessionClosed);
+    }
+}
@@ -0,0 +1,9 @@
+//! Static files and web UI assets.
+//!
+//! [`StaticFiles`] serves a directory, such as the repository's `web/`, or
+//! files embedded with [`embed_files!`](crate::embed_files) next to the MCP
+//! endpoints.
+
+mod static_files;
+
+pub use static_files::{EmbeddedFile, StaticFiles};
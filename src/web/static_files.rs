@@ -0,0 +1,685 @@
+//! Static file router.
+
+use std::{
+    fs,
+    io::{self, SeekFrom},
+    path::{Path as FsPath, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::get,
+    Router,
+};
+
+use crate::config::{safe_resolve_with, PathPolicy, SafePathError};
+use crate::mime;
+use crate::timestamp::{format_http_date, parse_http_date};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// A file embedded at compile time with [`embed_files!`](crate::embed_files).
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedFile {
+    /// Path relative to the served root, e.g. `assets/app.js`.
+    pub path: &'static str,
+    /// File contents.
+    pub data: &'static [u8],
+}
+
+/// Embed files below a directory of the calling crate.
+///
+/// Paths are relative to the directory, which is relative to the crate's
+/// `Cargo.toml`. Embed precompressed variants (`app.js.gz`, `app.js.br`) like
+/// any other file.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::{embed_files, web::StaticFiles};
+///
+/// static WEB: &[mcp_core::web::EmbeddedFile] =
+///     embed_files!("web", ["index.html", "app.js", "app.js.br"]);
+///
+/// let ui = StaticFiles::embedded(WEB).spa_fallback(true).router();
+/// ```
+#[macro_export]
+macro_rules! embed_files {
+    ($dir:literal, [$($path:literal),* $(,)?]) => {
+        &[$($crate::web::EmbeddedFile {
+            path: $path,
+            data: include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $dir, "/", $path)),
+        }),*]
+    };
+}
+
+#[derive(Clone, Debug)]
+enum Source {
+    Dir(PathBuf),
+    Embedded(&'static [EmbeddedFile]),
+}
+
+/// Precompressed variants, in order of preference.
+const ENCODINGS: [(&str, &str); 2] = [("br", ".br"), ("gzip", ".gz")];
+
+/// Builder for a router serving static files.
+///
+/// Serves `GET` and `HEAD` for every path below where it is mounted:
+///
+/// - files are looked up with [`safe_resolve_with`], so paths cannot leave
+///   the directory; hidden files are denied unless another [`PathPolicy`] is
+///   set
+/// - only regular files are served; FIFOs, sockets and devices are `404`
+/// - lookups run on the blocking thread pool and bodies are streamed, so a
+///   slow disk or a large file does not stall the runtime
+/// - `/` and directories serve their `index.html`
+/// - the `Content-Type` is guessed from the extension
+/// - responses carry an `ETag` and, for files on disk, `Last-Modified`;
+///   `If-None-Match` and `If-Modified-Since` are answered with
+///   `304 Not Modified`
+/// - `app.js.br` or `app.js.gz` is sent instead of `app.js` if it exists and
+///   the client accepts that encoding
+/// - a single `Range` is answered with `206 Partial Content`
+/// - with [`spa_fallback`](Self::spa_fallback), unknown paths without an
+///   extension serve `/index.html` so client-side routing works
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mcp_core::web::StaticFiles;
+///
+/// let app = sse_router
+///     .nest("/ui", StaticFiles::dir("web").spa_fallback(true).router())
+///     .layer(TokenAuthLayer::new(token));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StaticFiles {
+    source: Source,
+    policy: PathPolicy,
+    spa_fallback: bool,
+    cache_control: String,
+}
+
+impl StaticFiles {
+    /// Serve the files below `path`.
+    pub fn dir(path: impl Into<PathBuf>) -> Self {
+        Self::new(Source::Dir(path.into()))
+    }
+
+    /// Serve files embedded with [`embed_files!`](crate::embed_files).
+    pub fn embedded(files: &'static [EmbeddedFile]) -> Self {
+        Self::new(Source::Embedded(files))
+    }
+
+    fn new(source: Source) -> Self {
+        Self {
+            source,
+            policy: PathPolicy::new().deny_hidden(true),
+            spa_fallback: false,
+            cache_control: "no-cache".to_string(),
+        }
+    }
+
+    /// Restrict which files of a directory are served (default: no hidden
+    /// files).
+    pub fn policy(mut self, policy: PathPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Serve `/index.html` for unknown paths without an extension.
+    pub fn spa_fallback(mut self, enabled: bool) -> Self {
+        self.spa_fallback = enabled;
+        self
+    }
+
+    /// `Cache-Control` header of every response (default `no-cache`, which
+    /// lets clients cache but revalidate with the `ETag`).
+    pub fn cache_control(mut self, value: impl Into<String>) -> Self {
+        self.cache_control = value.into();
+        self
+    }
+
+    /// Build the router.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/", get(serve))
+            .route("/*path", get(serve))
+            .with_state(Arc::new(self))
+    }
+
+    /// Find `path`, preferring a precompressed variant accepted by the
+    /// client.
+    fn find(&self, path: &str, headers: &HeaderMap) -> Result<Asset, Lookup> {
+        for (encoding, suffix) in ENCODINGS {
+            if accepts_encoding(headers, encoding) {
+                if let Ok(mut asset) = self.find_exact(&format!("{path}{suffix}")) {
+                    asset.encoding = Some(encoding);
+                    return Ok(asset);
+                }
+            }
+        }
+        self.find_exact(path)
+    }
+
+    fn find_exact(&self, path: &str) -> Result<Asset, Lookup> {
+        match &self.source {
+            Source::Dir(base) => {
+                let resolved =
+                    safe_resolve_with(base, path, &self.policy).map_err(|e| match e {
+                        SafePathError::NotFound(_) => Lookup::NotFound,
+                        _ => Lookup::Denied,
+                    })?;
+                let file = open_nonblocking(&resolved).map_err(|_| Lookup::NotFound)?;
+                let metadata = file.metadata().map_err(|_| Lookup::NotFound)?;
+                if metadata.is_dir() {
+                    return Err(Lookup::Dir);
+                }
+                if !metadata.is_file() {
+                    return Err(Lookup::NotFound);
+                }
+                let modified = metadata.modified().ok();
+                let mtime = modified
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .unwrap_or_default();
+                Ok(Asset {
+                    data: Data::File(file),
+                    len: metadata.len(),
+                    modified,
+                    etag: format!("{:x}-{:x}", metadata.len(), mtime.as_nanos()),
+                    encoding: None,
+                })
+            }
+            Source::Embedded(files) => {
+                let file = files
+                    .iter()
+                    .find(|file| file.path == path)
+                    .ok_or(Lookup::NotFound)?;
+                Ok(Asset {
+                    data: Data::Static(file.data),
+                    len: file.data.len() as u64,
+                    modified: None,
+                    etag: format!("{:x}", fnv1a(file.data)),
+                    encoding: None,
+                })
+            }
+        }
+    }
+}
+
+/// Why a path could not be served.
+enum Lookup {
+    NotFound,
+    Denied,
+    Dir,
+}
+
+enum Data {
+    Static(&'static [u8]),
+    File(fs::File),
+}
+
+/// A file found for a request.
+struct Asset {
+    data: Data,
+    len: u64,
+    modified: Option<SystemTime>,
+    /// Opaque tag without quotes or encoding suffix.
+    etag: String,
+    encoding: Option<&'static str>,
+}
+
+impl Asset {
+    fn etag(&self) -> String {
+        match self.encoding {
+            Some(encoding) => format!("\"{}-{encoding}\"", self.etag),
+            None => format!("\"{}\"", self.etag),
+        }
+    }
+
+    /// Body streaming `len` bytes starting at `start`.
+    async fn body(self, start: u64, len: u64) -> io::Result<Body> {
+        match self.data {
+            Data::Static(data) => Ok(Body::from(&data[start as usize..(start + len) as usize])),
+            Data::File(file) => {
+                let mut file = tokio::fs::File::from_std(file);
+                file.seek(SeekFrom::Start(start)).await?;
+                Ok(Body::from_stream(ReaderStream::new(file.take(len))))
+            }
+        }
+    }
+}
+
+/// Open `path` for reading without blocking on FIFOs or devices.
+///
+/// `O_NONBLOCK` has no effect on the regular files that are served.
+#[cfg(unix)]
+fn open_nonblocking(path: &FsPath) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_nonblocking(path: &FsPath) -> io::Result<fs::File> {
+    fs::File::open(path)
+}
+
+/// [`StaticFiles::find`] on the blocking thread pool.
+async fn find(files: &Arc<StaticFiles>, path: &str, headers: &HeaderMap) -> Result<Asset, Lookup> {
+    let (files, path, headers) = (files.clone(), path.to_string(), headers.clone());
+    match tokio::task::spawn_blocking(move || files.find(&path, &headers)).await {
+        Ok(result) => result,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+async fn serve(
+    State(files): State<Arc<StaticFiles>>,
+    path: Option<Path<String>>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+) -> Response {
+    let path = path.map(|Path(path)| path).unwrap_or_default();
+    let path = if path.is_empty() || path.ends_with('/') {
+        format!("{path}index.html")
+    } else {
+        path
+    };
+
+    let asset = match find(&files, &path, &headers).await {
+        Ok(asset) => asset,
+        Err(Lookup::Dir) => {
+            return Redirect::permanent(&format!("{}/", uri.path())).into_response()
+        }
+        Err(Lookup::NotFound) if files.spa_fallback && !has_extension(&path) => {
+            match find(&files, "index.html", &headers).await {
+                Ok(asset) => asset,
+                Err(_) => return StatusCode::NOT_FOUND.into_response(),
+            }
+        }
+        Err(Lookup::Denied) => {
+            tracing::debug!(path, "static file request denied");
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    respond(&files, &path, asset, &headers).await
+}
+
+async fn respond(files: &StaticFiles, path: &str, asset: Asset, headers: &HeaderMap) -> Response {
+    let etag = asset.etag();
+    let last_modified = asset.modified.map(format_http_date);
+
+    let mut response_headers = HeaderMap::new();
+    let mut set = |name, value: &str| {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            response_headers.insert(name, value);
+        }
+    };
+    set(header::ETAG, &etag);
+    if let Some(last_modified) = &last_modified {
+        set(header::LAST_MODIFIED, last_modified);
+    }
+    set(header::CACHE_CONTROL, &files.cache_control);
+    set(header::VARY, "Accept-Encoding");
+
+    if not_modified(headers, &etag, asset.modified) {
+        return (StatusCode::NOT_MODIFIED, response_headers).into_response();
+    }
+
+    let mime_type = mime::guess(path);
+    if mime_type.starts_with("text/") {
+        set(header::CONTENT_TYPE, &format!("{mime_type}; charset=utf-8"));
+    } else {
+        set(header::CONTENT_TYPE, mime_type);
+    }
+    if let Some(encoding) = asset.encoding {
+        set(header::CONTENT_ENCODING, encoding);
+    } else {
+        set(header::ACCEPT_RANGES, "bytes");
+    }
+
+    let range = match asset.encoding {
+        None if if_range_matches(headers, &etag, last_modified.as_deref()) => headers
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_range(value, asset.len)),
+        _ => None,
+    };
+    let (status, start, len) = match range {
+        Some(Ok((start, end))) => {
+            set(
+                header::CONTENT_RANGE,
+                &format!("bytes {start}-{end}/{}", asset.len),
+            );
+            (StatusCode::PARTIAL_CONTENT, start, end - start + 1)
+        }
+        Some(Err(())) => {
+            set(header::CONTENT_RANGE, &format!("bytes */{}", asset.len));
+            return (StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response();
+        }
+        None => (StatusCode::OK, 0, asset.len),
+    };
+    // Streamed bodies have no size hint, so the length is set explicitly.
+    set(header::CONTENT_LENGTH, &len.to_string());
+
+    match asset.body(start, len).await {
+        Ok(body) => (status, response_headers, body).into_response(),
+        Err(e) => {
+            tracing::warn!(path, error = %e, "failed to read static file");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Whether `Accept-Encoding` lists `encoding` without `q=0`.
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    header_str(headers, header::ACCEPT_ENCODING).is_some_and(|value| {
+        value.split(',').any(|item| {
+            let mut params = item.split(';').map(str::trim);
+            params.next() == Some(encoding)
+                && !params.any(|param| {
+                    param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+                })
+        })
+    })
+}
+
+/// Whether the client's cached copy is current.
+fn not_modified(headers: &HeaderMap, etag: &str, modified: Option<SystemTime>) -> bool {
+    if let Some(value) = header_str(headers, header::IF_NONE_MATCH) {
+        return value
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+    }
+    match (
+        header_str(headers, header::IF_MODIFIED_SINCE).and_then(parse_http_date),
+        modified,
+    ) {
+        (Some(since), Some(modified)) => truncate_to_secs(modified) <= since,
+        _ => false,
+    }
+}
+
+/// Whether a `Range` may be applied, i.e. `If-Range` is absent or matches.
+fn if_range_matches(headers: &HeaderMap, etag: &str, last_modified: Option<&str>) -> bool {
+    match header_str(headers, header::IF_RANGE) {
+        None => true,
+        Some(value) if value.starts_with('"') => value == etag,
+        Some(value) => Some(value) == last_modified,
+    }
+}
+
+/// Parse a single `bytes=` range into inclusive bounds.
+///
+/// Returns `None` for ranges that are ignored (malformed or multiple) and
+/// `Some(Err(()))` for unsatisfiable ones.
+fn parse_range(value: &str, len: u64) -> Option<Result<(u64, u64), ()>> {
+    let spec = value.strip_prefix("bytes=")?.trim();
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let range = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 || len == 0 {
+                return Some(Err(()));
+            }
+            (len.saturating_sub(suffix), len - 1)
+        }
+        (start, end) => {
+            let start: u64 = start.parse().ok()?;
+            let end = match end {
+                "" => len.saturating_sub(1),
+                end => end.parse::<u64>().ok()?.min(len.saturating_sub(1)),
+            };
+            if start >= len || start > end {
+                return Some(Err(()));
+            }
+            (start, end)
+        }
+    };
+    Some(Ok(range))
+}
+
+fn has_extension(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'))
+}
+
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    UNIX_EPOCH + std::time::Duration::from_secs(secs)
+}
+
+/// 64-bit FNV-1a hash, used as the `ETag` of embedded files.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::util::ServiceExt;
+
+    async fn get(router: &Router, uri: &str, headers: &[(&str, &str)]) -> Response {
+        let mut request = Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    fn fixture() -> (tempfile::TempDir, Router) {
+        let dir = tempfile::tempdir().unwrap();
+        let web = dir.path().join("web");
+        fs::create_dir_all(web.join("assets")).unwrap();
+        fs::write(web.join("index.html"), "<html>app</html>").unwrap();
+        fs::write(web.join("assets/app.js"), "console.log(1)").unwrap();
+        fs::write(web.join("assets/app.js.gz"), "gzipped").unwrap();
+        fs::write(web.join(".env"), "SECRET=1").unwrap();
+        fs::write(dir.path().join("secret.txt"), "outside").unwrap();
+        let router = StaticFiles::dir(web).spa_fallback(true).router();
+        (dir, router)
+    }
+
+    #[tokio::test]
+    async fn serves_files_with_caching_headers() {
+        let (_dir, router) = fixture();
+
+        let response = get(&router, "/", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(response.headers().contains_key(header::LAST_MODIFIED));
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(body(response).await, b"<html>app</html>");
+
+        let response = get(&router, "/index.html", &[("If-None-Match", &etag)]).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = get(&router, "/assets/app.js", &[]).await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/javascript; charset=utf-8"
+        );
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "14");
+        assert_eq!(body(response).await, b"console.log(1)");
+
+        let request = Request::head("/assets/app.js").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "14");
+        assert!(body(response).await.is_empty());
+
+        let response = get(&router, "/assets", &[]).await;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()[header::LOCATION], "/assets/");
+    }
+
+    #[tokio::test]
+    async fn prefers_precompressed_variants() {
+        let (_dir, router) = fixture();
+        let response = get(
+            &router,
+            "/assets/app.js",
+            &[("Accept-Encoding", "br;q=0, gzip")],
+        )
+        .await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/javascript; charset=utf-8"
+        );
+        assert!(response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .ends_with("-gzip\""));
+        assert_eq!(body(response).await, b"gzipped");
+    }
+
+    #[tokio::test]
+    async fn answers_range_requests() {
+        let (_dir, router) = fixture();
+        let response = get(&router, "/assets/app.js", &[("Range", "bytes=8-")]).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 8-13/14");
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "6");
+        assert_eq!(body(response).await, b"log(1)");
+
+        let response = get(&router, "/assets/app.js", &[("Range", "bytes=100-")]).await;
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+        let response = get(
+            &router,
+            "/assets/app.js",
+            &[("Range", "bytes=0-1"), ("If-Range", "\"stale\"")],
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        assert_eq!(parse_range("bytes=-4", 14), Some(Ok((10, 13))));
+        assert_eq!(parse_range("bytes=0-1,4-5", 14), None);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_index_and_denies_hidden_files() {
+        let (_dir, router) = fixture();
+        let response = get(&router, "/settings/profile", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await, b"<html>app</html>");
+
+        for uri in [
+            "/missing.js",
+            "/.env",
+            "/../secret.txt",
+            "/%2e%2e/secret.txt",
+        ] {
+            let response = get(&router, uri, &[]).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn redirects_directories_with_an_extension_allow_list() {
+        let (dir, _) = fixture();
+        fs::write(
+            dir.path().join("web/assets/index.html"),
+            "<html>assets</html>",
+        )
+        .unwrap();
+        let router = StaticFiles::dir(dir.path().join("web"))
+            .policy(PathPolicy::new().allow_extension("html"))
+            .router();
+
+        let response = get(&router, "/assets", &[]).await;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        let response = get(&router, "/assets/", &[]).await;
+        assert_eq!(body(response).await, b"<html>assets</html>");
+        let response = get(&router, "/assets/app.js", &[]).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn streams_regular_files_only() {
+        let (dir, router) = fixture();
+        let web = dir.path().join("web");
+        let large: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+        fs::write(web.join("large.bin"), &large).unwrap();
+        let response = get(&router, "/large.bin", &[]).await;
+        assert_eq!(body(response).await, large);
+        let response = get(&router, "/large.bin", &[("Range", "bytes=100000-100009")]).await;
+        assert_eq!(body(response).await, &large[100_000..100_010]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let fifo = std::ffi::CString::new(web.join("pipe.txt").as_os_str().as_bytes()).unwrap();
+            assert_eq!(unsafe { libc::mkfifo(fifo.as_ptr(), 0o600) }, 0);
+            let response = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                get(&router, "/pipe.txt", &[]),
+            )
+            .await
+            .expect("FIFO lookup blocked");
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_embedded_files() {
+        static FILES: &[EmbeddedFile] = &[EmbeddedFile {
+            path: "index.html",
+            data: b"<html>embedded</html>",
+        }];
+        let router = StaticFiles::embedded(FILES).router();
+        let response = get(&router, "/", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::LAST_MODIFIED));
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(body(response).await, b"<html>embedded</html>");
+
+        let response = get(&router, "/", &[("If-None-Match", &etag)]).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+}